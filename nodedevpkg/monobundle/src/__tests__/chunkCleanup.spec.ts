@@ -0,0 +1,54 @@
+import { describe, expect, it } from "vitest";
+import type { ReportEntry } from "@innoai-tech/nativebundle";
+import { chunkCleanup } from "../chunkCleanup";
+
+const renderChunk = async (
+  plugin: ReturnType<typeof chunkCleanup>,
+  code: string,
+  fileName: string,
+) => {
+  return (plugin.renderChunk as any).call({}, code, { fileName });
+};
+
+describe("chunkCleanup", () => {
+  it("report with minify", async () => {
+    const reports: { [fileName: string]: ReportEntry[] } = {};
+
+    const code = await renderChunk(
+      chunkCleanup({
+        minify: true,
+        purebundle: { report: true },
+        onReport: (fileName, report) => {
+          reports[fileName] = report;
+        },
+      }),
+      `import "fs";\nexport const a = create();\n`,
+      "index.mjs",
+    );
+
+    expect(code).not.toContain("__PUREBUNDLE_REPORT__");
+    expect(
+      reports["index.mjs"]?.map((entry) => [entry.rule, entry.text]),
+    ).toEqual([
+      ["ignoreSideImports", "fs"],
+      ["annotatePureCalls", "create"],
+    ]);
+  });
+
+  it("no report without the option", async () => {
+    const reports: ReportEntry[][] = [];
+
+    await renderChunk(
+      chunkCleanup({
+        minify: true,
+        onReport: (_, report) => {
+          reports.push(report);
+        },
+      }),
+      `export const a = create();\n`,
+      "index.mjs",
+    );
+
+    expect(reports).toEqual([]);
+  });
+});
@@ -1,4 +1,5 @@
-import { transform, usePlugin } from "@innoai-tech/purebundle";
+import { transform, type usePlugin } from "@innoai-tech/purebundle";
+import { purebundle, type ReportEntry } from "@innoai-tech/nativebundle";
 import { type Plugin } from "rolldown";
 
 export const chunkCleanup = (
@@ -11,18 +12,43 @@ export const chunkCleanup = (
       exclude?: string[];
       include?: string[];
     };
+    purebundle?: Parameters<typeof usePlugin>[0] & { report?: boolean };
+    onReport?: (fileName: string, report: ReportEntry[]) => void;
   } = {},
 ): Plugin => {
   return {
     name: "monobundle/chunk-cleanup",
 
-    async renderChunk(code: string) {
-      return (
-        await transform(code, {
+    async renderChunk(code: string, chunk) {
+      // passes run natively, @swc/core only minifies without wasm plugins,
+      // so not coupled to the plugin ABI of the host @swc/core,
+      // and report is taken before minify, which drops all comments
+      const { code: cleaned, report } = purebundle(code, {
+        filename: chunk.fileName,
+        config: opt.purebundle ?? {},
+      });
+
+      const transformed = (
+        await transform(cleaned, {
           minify: opt.minify ?? false,
-          plugins: [usePlugin({})],
         })
       ).code;
+
+      if (!opt.purebundle?.report) {
+        return transformed;
+      }
+
+      if (opt.onReport) {
+        opt.onReport(chunk.fileName, report);
+      } else {
+        for (const entry of report) {
+          console.info(
+            `[purebundle] ${chunk.fileName}:${entry.line}:${entry.column} ${entry.rule} (${entry.reason}) ${entry.text}`,
+          );
+        }
+      }
+
+      return transformed;
     },
   };
 };
@@ -1,6 +1,6 @@
 import { extname } from "path";
 import type { Plugin } from "rolldown";
-import { transform, usePlugin } from "@innoai-tech/vuecomponentcompleter";
+import { vueComponentComplete as complete } from "@innoai-tech/nativebundle";
 
 export const vueComponentComplete = ({}): Plugin => {
   return {
@@ -10,10 +10,10 @@ export const vueComponentComplete = ({}): Plugin => {
       const ext = extname(id);
 
       if (ext == ".tsx" || ext == ".ts") {
-        const result = await transform(code, {
+        // types are kept, which are stripped by rolldown later
+        const result = complete(code, {
           filename: id,
-          minify: false,
-          plugins: [usePlugin({})],
+          sourceMap: true,
         });
 
         return (
@@ -0,0 +1,60 @@
+import { describe, expect, it } from "vitest";
+import { purebundle, vueComponentComplete } from "..";
+
+describe("purebundle", () => {
+  it("run passes like the swc plugin", () => {
+    const { code, report } = purebundle(
+      `import "fs";\nexport const a = create();\n`,
+      { filename: "index.mjs", config: { report: true } },
+    );
+
+    expect(code).toEqual("export const a = /*#__PURE__*/ create();\n");
+    expect(
+      report.map((entry) => [entry.rule, entry.text, entry.line, entry.column]),
+    ).toEqual([
+      ["ignoreSideImports", "fs", 1, 0],
+      ["annotatePureCalls", "create", 2, 17],
+    ]);
+  });
+
+  it("chain source map to input source map", () => {
+    const { map } = purebundle(`\n\nexport const a = create();\n`, {
+      filename: "index.mjs",
+      sourceMap: true,
+      inputSourceMap: `{"version":3,"sources":["src/index.ts"],"names":[],"mappings":";;AAEA"}`,
+    });
+
+    expect(JSON.parse(map!).sources).toEqual(["src/index.ts"]);
+  });
+
+  it("throw on invalid options", () => {
+    expect(() => purebundle("", { config: { unknown: true } })).toThrow(
+      /invalid purebundle options/,
+    );
+  });
+
+  it("throw on syntax errors", () => {
+    expect(() =>
+      purebundle("export const = 1", { filename: "index.mjs" }),
+    ).toThrow(/^index\.mjs:1:/);
+  });
+});
+
+describe("vueComponentComplete", () => {
+  it("complete components with types kept", () => {
+    const { code, map } = vueComponentComplete(
+      `export const X = component<{ active?: boolean }>(() => <div />);\n`,
+      { filename: "X.tsx", sourceMap: true },
+    );
+
+    expect(code).toContain(`displayName: "X"`);
+    expect(code).toContain(`active?: boolean;`);
+    expect(map).toBeDefined();
+  });
+
+  it("throw on syntax errors", () => {
+    expect(() =>
+      vueComponentComplete("const x: = 1", { filename: "x.ts" }),
+    ).toThrow(/^x\.ts:1:/);
+  });
+});
@@ -0,0 +1,40 @@
+export interface ReportEntry {
+  // option name of the pass, like `annotatePureCalls`
+  rule: string;
+  reason: string;
+  // callee, binding name or import source
+  text: string;
+  lo: number;
+  hi: number;
+  line: number;
+  column: number;
+}
+
+// run purebundle passes without @swc/core,
+// `config` is same as options of `usePlugin` of @innoai-tech/purebundle
+export function purebundle(
+  code: string,
+  opts?: {
+    filename?: string;
+    config?: { [K: string]: any };
+    sourceMap?: boolean;
+    inputSourceMap?: string;
+  },
+): {
+  code: string;
+  map?: string;
+  report: ReportEntry[];
+};
+
+// complete `displayName`, `props` and `emits` of vue components without @swc/core,
+// types are kept, `.tsx` filename enables jsx
+export function vueComponentComplete(
+  code: string,
+  opts?: {
+    filename?: string;
+    sourceMap?: boolean;
+  },
+): {
+  code: string;
+  map?: string;
+};
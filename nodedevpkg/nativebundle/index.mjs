@@ -0,0 +1,28 @@
+import { createRequire } from "module";
+
+// `nativebundle.<platform>.node` built by `napi build --platform` for each target in CI
+const platforms = {
+  "darwin-arm64": "darwin-arm64",
+  "darwin-x64": "darwin-x64",
+  "linux-arm64": "linux-arm64-gnu",
+  "linux-x64": "linux-x64-gnu",
+  "win32-x64": "win32-x64-msvc",
+};
+
+const platform = platforms[`${process.platform}-${process.arch}`];
+
+if (!platform) {
+  throw new Error(
+    `@innoai-tech/nativebundle: unsupported platform ${process.platform}-${process.arch}`,
+  );
+}
+
+const binding = createRequire(import.meta.url)(`./nativebundle.${platform}.node`);
+
+export function purebundle(code, opts = {}) {
+  return binding.purebundle(code, opts);
+}
+
+export function vueComponentComplete(code, opts = {}) {
+  return binding.vueComponentComplete(code, opts);
+}
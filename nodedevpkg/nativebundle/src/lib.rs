@@ -0,0 +1,94 @@
+use napi::{Error, Result};
+use napi_derive::napi;
+use serde_json::Value;
+
+#[napi(object)]
+pub struct PurebundleOptions {
+    pub filename: Option<String>,
+    /// same as options of the swc plugin
+    pub config: Option<Value>,
+    pub source_map: Option<bool>,
+    pub input_source_map: Option<String>,
+}
+
+#[napi(object)]
+pub struct PurebundleOutput {
+    pub code: String,
+    pub map: Option<String>,
+    /// entries of every transformation when `config.report`
+    pub report: Value,
+}
+
+/// Run purebundle passes on JS code, without `@swc/core`.
+#[napi]
+pub fn purebundle(code: String, options: Option<PurebundleOptions>) -> Result<PurebundleOutput> {
+    let options = options.unwrap_or(PurebundleOptions {
+        filename: None,
+        config: None,
+        source_map: None,
+        input_source_map: None,
+    });
+
+    let config = match options.config {
+        Some(config) => purebundle::Config::from_json(&config.to_string()),
+        None => Ok(Default::default()),
+    }
+    .map_err(Error::from_reason)?;
+
+    let output = purebundle::transform_code(
+        &code,
+        &purebundle::TransformOptions {
+            filename: options.filename.unwrap_or_else(|| "index.mjs".into()),
+            config,
+            source_map: options.source_map.unwrap_or(false),
+            input_source_map: options.input_source_map,
+        },
+    )
+    .map_err(Error::from_reason)?;
+
+    Ok(PurebundleOutput {
+        code: output.code,
+        map: output.map,
+        report: serde_json::to_value(&output.report)
+            .map_err(|err| Error::from_reason(err.to_string()))?,
+    })
+}
+
+#[napi(object)]
+pub struct VueComponentCompleteOptions {
+    /// `.tsx` enables jsx
+    pub filename: Option<String>,
+    pub source_map: Option<bool>,
+}
+
+#[napi(object)]
+pub struct VueComponentCompleteOutput {
+    pub code: String,
+    pub map: Option<String>,
+}
+
+/// Complete `displayName`, `props` and `emits` of Vue components in TS code, without `@swc/core`.
+#[napi]
+pub fn vue_component_complete(
+    code: String,
+    options: Option<VueComponentCompleteOptions>,
+) -> Result<VueComponentCompleteOutput> {
+    let options = options.unwrap_or(VueComponentCompleteOptions {
+        filename: None,
+        source_map: None,
+    });
+
+    let output = vuecomponentcompleter::transform_code(
+        &code,
+        &vuecomponentcompleter::TransformOptions {
+            filename: options.filename.unwrap_or_else(|| "index.tsx".into()),
+            source_map: options.source_map.unwrap_or(false),
+        },
+    )
+    .map_err(Error::from_reason)?;
+
+    Ok(VueComponentCompleteOutput {
+        code: output.code,
+        map: output.map,
+    })
+}
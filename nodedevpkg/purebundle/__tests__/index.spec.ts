@@ -149,6 +149,92 @@ const B = /*#__PURE__*/String("b");`,
     src: "const add = (x, y) => x + y; export const one = add(0, 1);",
     dest: "const add = (x, y) => x + y; export const one = /*#__PURE__*/add(0, 1);",
   },
+  {
+    title: "Annotated #__PURE__ for new expression",
+    src: "const cache = new Map(); export const bus = new EventEmitter();",
+    dest: "const cache = /*#__PURE__*/new Map(); export const bus = /*#__PURE__*/new EventEmitter();",
+  },
+  {
+    title: "Annotated #__PURE__ for new expression as array item, object property and parameter",
+    src: "([new Map()]); ({ x: new Map() }); export const a = fn(new Map());",
+    dest: "([/*#__PURE__*/new Map()]); ({ x: /*#__PURE__*/new Map() }); export const a = /*#__PURE__*/fn(/*#__PURE__*/new Map());",
+  },
+  {
+    title: "Skip annotated #__PURE__ for new expression when with variable used in param callback",
+    src: "const o = new MutationObserver(() => o.disconnect())",
+    dest: "const o = new MutationObserver(() => o.disconnect())",
+  },
+  {
+    title: "Skip #__PURE__ for known side effect globals",
+    src: `const t = setTimeout(init, 0); const off = window.addEventListener("load", init); const l = console.log("x");`,
+    dest: `const t = setTimeout(init, 0); const off = window.addEventListener("load", init); const l = console.log("x");`,
+  },
+  {
+    title: "Annotated #__PURE__ for destructuring declarator",
+    src: "const { a, b } = createStore(); const [state, setState] = useThing();",
+    dest: "const { a, b } = /*#__PURE__*/createStore(); const [state, setState] = /*#__PURE__*/useThing();",
+  },
+  {
+    title: "Skip annotated #__PURE__ for destructuring declarator when with binding used in param callback",
+    src: "const [state, setState] = useThing(() => setState(1));",
+    dest: "const [state, setState] = useThing(() => setState(1));",
+  },
+  {
+    title: "Skip annotated #__PURE__ when with variable used in param callback in assignment expression",
+    src: "let a; a = poll(() => { console.log(a) })",
+    dest: "let a; a = poll(() => { console.log(a) })",
+  },
+  {
+    title: "Skip annotated #__PURE__ when with member root used in param callback in assignment expression",
+    src: "b.timer = poll(() => { b.tick() })",
+    dest: "b.timer = poll(() => { b.tick() })",
+  },
+  {
+    title: "Annotated #__PURE__ for tagged template",
+    src: "export const Button = styled.div`color: red;`;",
+    dest: "export const Button = /*#__PURE__*/styled.div`color: red;`;",
+  },
+  {
+    title: "Annotated #__PURE__ for optional call and member call chain",
+    src: "export const a = api?.create(); export const b = builder().with(x).build();",
+    dest: "export const a = /*#__PURE__*/api?.create(); export const b = /*#__PURE__*/builder().with(x).build();",
+  },
+  {
+    title: "Skip #__PURE__ for member call chain of known side effect globals",
+    src: `export const d = fetch("/api").then((r) => r.json());`,
+    dest: `export const d = fetch("/api").then((r) => r.json());`,
+  },
+  {
+    title: "Annotated #__PURE__ for conditional and logical expressions",
+    src: "const x = isDev ? createDevStore() : createStore(); export const y = cached ?? compute();",
+    dest: "const x = isDev ? /*#__PURE__*/createDevStore() : /*#__PURE__*/createStore(); export const y = cached ?? /*#__PURE__*/compute();",
+  },
+  {
+    title: "Annotated #__PURE__ for last expression of sequence",
+    src: "export const d = (init(), create());",
+    dest: "export const d = (init(), /*#__PURE__*/create());",
+  },
+  {
+    title: "Annotated #__PURE__ for export default",
+    src: "export default defineConfig({ x: 1 });",
+    dest: "export default /*#__PURE__*/defineConfig({ x: 1 });",
+  },
+  {
+    title: "Annotated #__PURE__ for class fields",
+    src: "class Store { static instance = create(); #cache = new Map(); }",
+    dest: "class Store { static instance = /*#__PURE__*/create(); #cache = /*#__PURE__*/new Map(); }",
+  },
+  {
+    title: "Folded static property assignments into pure IIFE",
+    options: { foldStaticProps: { enabled: true } },
+    src: `export const Button = component(); Button.displayName = "Button";`,
+    dest: `export const Button = /*#__PURE__*/(() => { const Button = /*#__PURE__*/component(); Button.displayName = "Button"; return Button; })();`,
+  },
+  {
+    title: "Folded TS enum IIFE into pure call",
+    src: `export var E; (function (E) { E[E["A"] = 0] = "A"; })(E || (E = {}));`,
+    dest: `export var E = /*#__PURE__*/(function (E) { E[E["A"] = 0] = "A"; return E; })({});`,
+  },
 ];
 
 function unPad(str: string) {
@@ -160,7 +246,7 @@ describe("test cases", () => {
     ((caseItem as any).only ? it.only : it)(caseItem.title, async () => {
       const transformedCode = (
         await transform(caseItem.src, {
-          plugins: [usePlugin({})],
+          plugins: [usePlugin((caseItem as any).options ?? {})],
         })
       ).code;
 
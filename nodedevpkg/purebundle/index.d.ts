@@ -0,0 +1,40 @@
+export function usePlugin(opts: {}): [string, any];
+
+import { TransformConfig } from "@swc/core";
+
+export function transform(
+  code: string,
+  opts: {
+    filename?: string;
+    minify?: boolean;
+    env?: {
+      targets?: string | string[] | { [K: string]: string };
+      mode?: string;
+      coreJs?: string;
+      exclude?: string[];
+      include?: string[];
+    };
+    plugins?: Array<[string, any]>;
+  },
+): Promise<{
+  code: string;
+  map?: string;
+}>;
+
+export interface ReportEntry {
+  // option name of the pass, like `annotatePureCalls`
+  rule: string;
+  reason: string;
+  // callee, binding name or import source
+  text: string;
+  lo: number;
+  hi: number;
+  line: number;
+  column: number;
+}
+
+// take `/*#__PUREBUNDLE_REPORT__ [...]*/` out of code transformed with `report: true`
+export function extractReport(code: string): {
+  code: string;
+  report: ReportEntry[];
+};
@@ -0,0 +1,282 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use swc_core::common::comments::{Comment, CommentKind, Comments};
+use swc_core::common::{BytePos, DUMMY_SP};
+use swc_core::ecma::ast::{
+    Decl, DefaultDecl, ExportSpecifier, Expr, Id, Ident, Module, ModuleDecl, ModuleExportName,
+    ModuleItem, Pass, Pat, Stmt, VarDeclKind,
+};
+use swc_core::ecma::visit::{noop_visit_mut_type, visit_mut_pass, VisitMut};
+
+use crate::annotate_pure_calls::purity::Purity;
+use crate::annotate_pure_calls::{impure_callees, AnnotatePureCallsConfig};
+use crate::pattern::{matches_any, Pattern};
+use crate::report::report;
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct AnnotateNoSideEffectsConfig {
+    pub enabled: bool,
+    /// names of exported functions to annotate, like `create*` or `/^define[A-Z]/`
+    pub names: Vec<Pattern>,
+    /// annotate exported functions proved pure by purity analysis of annotatePureCalls sound mode,
+    /// off by default as call sites of them will be dropped by bundlers once annotated
+    pub analyze: bool,
+}
+
+pub fn annotate_no_side_effects<C>(
+    comments: C,
+    config: &AnnotateNoSideEffectsConfig,
+    pure_calls: &AnnotatePureCallsConfig,
+) -> impl VisitMut + Pass
+where
+    C: Comments + Clone,
+{
+    visit_mut_pass(NoSideEffectsAnnotation {
+        comments: Some(comments),
+        names: config.names.clone(),
+        analyze: config.analyze,
+        pure_imports: pure_calls.pure_imports.clone(),
+        impure_callees: impure_callees(pure_calls),
+    })
+}
+
+struct NoSideEffectsAnnotation<C: Comments>
+where
+    C: Comments + Clone,
+{
+    comments: Option<C>,
+    names: Vec<Pattern>,
+    analyze: bool,
+    pure_imports: Vec<Pattern>,
+    impure_callees: Vec<Pattern>,
+}
+
+impl<C> NoSideEffectsAnnotation<C>
+where
+    C: Comments + Clone,
+{
+    fn annotate(&self, pos: BytePos, ident: &Ident, reason: &'static str) {
+        if let Some(comments) = &self.comments {
+            if !comments.has_flag(pos, "NO_SIDE_EFFECTS") {
+                comments.add_leading(
+                    pos,
+                    Comment {
+                        kind: CommentKind::Block,
+                        span: DUMMY_SP,
+                        text: "#__NO_SIDE_EFFECTS__".into(),
+                    },
+                );
+
+                report("annotateNoSideEffects", reason, ident.span, || {
+                    ident.sym.to_string()
+                });
+            }
+        }
+    }
+}
+
+impl<C> VisitMut for NoSideEffectsAnnotation<C>
+where
+    C: Comments + Clone,
+{
+    noop_visit_mut_type!();
+
+    fn visit_mut_module(&mut self, module: &mut Module) {
+        let purity = if self.analyze {
+            Some(Purity::new(
+                module,
+                self.pure_imports.clone(),
+                self.impure_callees.clone(),
+            ))
+        } else {
+            None
+        };
+
+        let exported = collect_exported(module);
+
+        // reason to annotate
+        let should_annotate = |ident: &Ident| {
+            let id = ident.to_id();
+
+            if !exported.contains(&id) {
+                None
+            } else if matches_any(&self.names, &ident.sym) {
+                Some("names")
+            } else if purity
+                .as_ref()
+                .is_some_and(|purity| purity.is_pure_local_fn(&id))
+            {
+                Some("analyze")
+            } else {
+                None
+            }
+        };
+
+        for item in &module.body {
+            let decl = match item {
+                ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => &export_decl.decl,
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export_default_decl)) => {
+                    if let DefaultDecl::Fn(fn_expr) = &export_default_decl.decl {
+                        if let Some(ident) = &fn_expr.ident {
+                            if let Some(reason) = should_annotate(ident) {
+                                self.annotate(fn_expr.function.span.lo, ident, reason);
+                            }
+                        }
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            match decl {
+                // export function createX() {}
+                Decl::Fn(fn_decl) => {
+                    if let Some(reason) = should_annotate(&fn_decl.ident) {
+                        self.annotate(fn_decl.function.span.lo, &fn_decl.ident, reason);
+                    }
+                }
+                // export const createX = () => {}
+                Decl::Var(var_decl) if var_decl.kind == VarDeclKind::Const => {
+                    for decl in &var_decl.decls {
+                        let (Pat::Ident(ident), Some(init)) = (&decl.name, &decl.init) else {
+                            continue;
+                        };
+
+                        let pos = match init.unwrap_parens() {
+                            Expr::Fn(fn_expr) => fn_expr.function.span.lo,
+                            Expr::Arrow(arrow_expr) => arrow_expr.span.lo,
+                            _ => continue,
+                        };
+
+                        if let Some(reason) = should_annotate(&ident.id) {
+                            self.annotate(pos, &ident.id, reason);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// local bindings exported by `export function`, `export const`, `export default function` or `export { x }`
+fn collect_exported(module: &Module) -> HashSet<Id> {
+    let mut exported = HashSet::new();
+
+    for item in &module.body {
+        let ModuleItem::ModuleDecl(module_decl) = item else {
+            continue;
+        };
+
+        match module_decl {
+            ModuleDecl::ExportDecl(export_decl) => match &export_decl.decl {
+                Decl::Fn(fn_decl) => {
+                    exported.insert(fn_decl.ident.to_id());
+                }
+                Decl::Var(var_decl) => {
+                    for decl in &var_decl.decls {
+                        if let Pat::Ident(ident) = &decl.name {
+                            exported.insert(ident.to_id());
+                        }
+                    }
+                }
+                _ => {}
+            },
+            ModuleDecl::ExportDefaultDecl(export_default_decl) => {
+                if let DefaultDecl::Fn(fn_expr) = &export_default_decl.decl {
+                    if let Some(ident) = &fn_expr.ident {
+                        exported.insert(ident.to_id());
+                    }
+                }
+            }
+            ModuleDecl::ExportNamed(named_export) if named_export.src.is_none() => {
+                for specifier in &named_export.specifiers {
+                    if let ExportSpecifier::Named(named) = specifier {
+                        if let ModuleExportName::Ident(ident) = &named.orig {
+                            exported.insert(ident.to_id());
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    exported
+}
+
+#[cfg(test)]
+mod test {
+    use super::{annotate_no_side_effects, AnnotateNoSideEffectsConfig};
+    use crate::pattern::Pattern;
+    use crate::testing::assert_transform;
+
+    #[test]
+    fn exported_functions_matched_or_proved_pure_should_be_annotated() {
+        assert_transform(
+            |comments| {
+                annotate_no_side_effects(
+                    comments,
+                    &AnnotateNoSideEffectsConfig {
+                        enabled: true,
+                        names: vec![Pattern::try_from("create*".to_string()).unwrap()],
+                        analyze: true,
+                    },
+                    &Default::default(),
+                )
+            },
+            r#"
+            let count = 0;
+            export function createStore() { count++; return {} }
+            export const add = (x, y) => x + y;
+            export const inc = () => count++;
+            export const touch = (o) => { const p = o; p.touched = true; return p };
+            export const mul = function (x, y) { return x * y };
+            function sub(x, y) { return x - y }
+            function local(x, y) { return x - y }
+            export { sub as minus };
+            export default function div(x, y) { return x / y }
+            "#,
+            r#"
+            let count = 0;
+            export /*#__NO_SIDE_EFFECTS__*/ function createStore() { count++; return {} }
+            export const add = /*#__NO_SIDE_EFFECTS__*/ (x, y) => x + y;
+            export const inc = () => count++;
+            export const touch = (o) => { const p = o; p.touched = true; return p };
+            export const mul = /*#__NO_SIDE_EFFECTS__*/ function (x, y) { return x * y };
+            /*#__NO_SIDE_EFFECTS__*/ function sub(x, y) { return x - y }
+            function local(x, y) { return x - y }
+            export { sub as minus };
+            export default /*#__NO_SIDE_EFFECTS__*/ function div(x, y) { return x / y }
+            "#,
+        );
+    }
+
+    #[test]
+    fn only_functions_matched_should_be_annotated_without_analyze() {
+        assert_transform(
+            |comments| {
+                annotate_no_side_effects(
+                    comments,
+                    &AnnotateNoSideEffectsConfig {
+                        enabled: true,
+                        names: vec![Pattern::try_from("/^define[A-Z]/".to_string()).unwrap()],
+                        analyze: false,
+                    },
+                    &Default::default(),
+                )
+            },
+            r#"
+            export const defineConfig = (c) => c;
+            export const add = (x, y) => x + y;
+            "#,
+            r#"
+            export const defineConfig = /*#__NO_SIDE_EFFECTS__*/ (c) => c;
+            export const add = (x, y) => x + y;
+            "#,
+        );
+    }
+}
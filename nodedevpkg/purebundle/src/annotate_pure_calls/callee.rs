@@ -0,0 +1,180 @@
+use swc_core::common::BytePos;
+use swc_core::ecma::ast::{Callee, Expr, Id, Lit, MemberExpr, MemberProp, OptChainBase};
+
+use crate::pattern::Pattern;
+
+// globals known with side effects, result of which usually unused.
+pub const IMPURE_CALLEES: [&str; 12] = [
+    "setTimeout",
+    "setInterval",
+    "setImmediate",
+    "requestAnimationFrame",
+    "requestIdleCallback",
+    "queueMicrotask",
+    "addEventListener",
+    "removeEventListener",
+    "console.*",
+    "fetch",
+    "customElements.define",
+    "Object.defineProperty",
+];
+
+/// `a.b.c()` => `a.b.c`
+pub fn callee_path(expr: &Expr) -> Option<String> {
+    match expr.unwrap_parens() {
+        Expr::Ident(ident) => Some(ident.sym.to_string()),
+        Expr::This(_) => Some("this".into()),
+        Expr::OptChain(opt_chain_expr) => match &*opt_chain_expr.base {
+            OptChainBase::Member(member_expr) => member_path(member_expr),
+            OptChainBase::Call(_) => None,
+        },
+        Expr::Member(member_expr) => member_path(member_expr),
+        _ => None,
+    }
+}
+
+fn member_path(member_expr: &MemberExpr) -> Option<String> {
+    let obj = callee_path(&member_expr.obj)?;
+
+    let prop = match &member_expr.prop {
+        MemberProp::Ident(ident) => ident.sym.to_string(),
+        MemberProp::Computed(computed) => match computed.expr.as_ref() {
+            Expr::Lit(Lit::Str(s)) => s.value.to_string_lossy().to_string(),
+            _ => return None,
+        },
+        MemberProp::PrivateName(_) => return None,
+    };
+
+    Some(format!("{}.{}", obj, prop))
+}
+
+/// Position to place `/*#__PURE__*/` and callees of the whole chain for
+/// call, new, optional call and tagged template.
+///
+/// All calls of a chain like `builder().with(x).build()` start at same position,
+/// so they are annotated together by minifiers, each callee of chain should be checked.
+pub fn pure_target(expr: &Expr) -> Option<(BytePos, Vec<&Expr>)> {
+    let (pos, callee) = match expr.unwrap_parens() {
+        Expr::Call(call_expr) => match &call_expr.callee {
+            Callee::Expr(callee) => (call_expr.span.lo, Some(callee.as_ref())),
+            _ => (call_expr.span.lo, None),
+        },
+        Expr::New(new_expr) => (new_expr.span.lo, Some(new_expr.callee.as_ref())),
+        Expr::TaggedTpl(tagged_tpl) => (tagged_tpl.span.lo, Some(tagged_tpl.tag.as_ref())),
+        Expr::OptChain(opt_chain_expr) => match &*opt_chain_expr.base {
+            OptChainBase::Call(opt_call) => {
+                (opt_chain_expr.span.lo, Some(opt_call.callee.as_ref()))
+            }
+            OptChainBase::Member(_) => return None,
+        },
+        _ => return None,
+    };
+
+    let mut callees = vec![];
+
+    if let Some(callee) = callee {
+        collect_chain_callees(callee, &mut callees);
+    }
+
+    Some((pos, callees))
+}
+
+fn collect_chain_callees<'a>(callee: &'a Expr, callees: &mut Vec<&'a Expr>) {
+    callees.push(callee);
+
+    let obj = match callee.unwrap_parens() {
+        Expr::Member(member_expr) => &member_expr.obj,
+        Expr::OptChain(opt_chain_expr) => match &*opt_chain_expr.base {
+            OptChainBase::Member(member_expr) => &member_expr.obj,
+            OptChainBase::Call(_) => return,
+        },
+        _ => return,
+    };
+
+    if let Some((_, inner)) = pure_target(obj) {
+        callees.extend(inner);
+    }
+}
+
+/// `a.b.c` => `a`
+pub fn member_root(member_expr: &MemberExpr) -> Option<Id> {
+    match member_expr.obj.unwrap_parens() {
+        Expr::Ident(ident) => Some(ident.to_id()),
+        Expr::Member(member_expr) => member_root(member_expr),
+        _ => None,
+    }
+}
+
+/// Pattern matches the path or any tail of it,
+/// so `setTimeout` matches `window.setTimeout` and `addEventListener` matches `el.addEventListener`
+pub fn matches_callee(patterns: &[Pattern], path: &str) -> bool {
+    let mut tail = path;
+
+    loop {
+        if patterns.iter().any(|pattern| pattern.matches(tail)) {
+            return true;
+        }
+
+        match tail.find('.') {
+            Some(i) => tail = &tail[i + 1..],
+            None => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{callee_path, matches_callee, pure_target, IMPURE_CALLEES};
+    use crate::pattern::Pattern;
+
+    fn parse_expr(src: &str) -> Box<swc_core::ecma::ast::Expr> {
+        use swc_core::common::{sync::Lrc, FileName, SourceMap};
+        use swc_core::ecma::parser::{parse_file_as_expr, EsSyntax, Syntax};
+
+        let cm: Lrc<SourceMap> = Default::default();
+        let fm = cm.new_source_file(FileName::Anon.into(), src.to_string());
+
+        parse_file_as_expr(
+            &fm,
+            Syntax::Es(EsSyntax::default()),
+            Default::default(),
+            None,
+            &mut vec![],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn chain_callees_should_be_collected() {
+        let expr = parse_expr("fetch(url).then(r => r.json()).catch(noop)");
+        let (_, callees) = pure_target(&expr).unwrap();
+        let paths: Vec<Option<String>> = callees.into_iter().map(callee_path).collect();
+
+        assert_eq!(paths, vec![None, None, Some("fetch".into())]);
+
+        let expr = parse_expr("api?.create()");
+        let (_, callees) = pure_target(&expr).unwrap();
+        let paths: Vec<Option<String>> = callees.into_iter().map(callee_path).collect();
+
+        assert_eq!(paths, vec![Some("api.create".into())]);
+
+        assert!(pure_target(&parse_expr("api?.x")).is_none());
+    }
+
+    #[test]
+    fn callee_should_match_by_tail() {
+        let patterns: Vec<Pattern> = IMPURE_CALLEES
+            .iter()
+            .map(|p| Pattern::try_from(p.to_string()).unwrap())
+            .collect();
+
+        assert!(matches_callee(&patterns, "setTimeout"));
+        assert!(matches_callee(&patterns, "window.setTimeout"));
+        assert!(matches_callee(&patterns, "document.body.addEventListener"));
+        assert!(matches_callee(&patterns, "console.log"));
+        assert!(matches_callee(&patterns, "window.customElements.define"));
+        assert!(!matches_callee(&patterns, "define"));
+        assert!(!matches_callee(&patterns, "createStore"));
+        assert!(!matches_callee(&patterns, "timeout.setTimeoutLater"));
+    }
+}
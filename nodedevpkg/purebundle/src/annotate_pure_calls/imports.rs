@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use swc_core::ecma::ast::{
+    Expr, Id, ImportSpecifier, MemberProp, Module, ModuleDecl, ModuleExportName, ModuleItem,
+};
+
+use super::callee::{callee_path, member_root};
+use crate::pattern::{matches_any, Pattern};
+
+/// `import { a as b } from "x"` => `b` from `x#a`
+#[derive(Debug, Clone)]
+pub struct ImportBinding {
+    pub src: String,
+    pub imported: Imported,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Imported {
+    Default,
+    Namespace,
+    Named(String),
+}
+
+impl ImportBinding {
+    /// `x#a` for named or default (`x#default`), `x` for namespace
+    pub fn path(&self) -> String {
+        match &self.imported {
+            Imported::Default => format!("{}#default", self.src),
+            Imported::Named(name) => format!("{}#{}", self.src, name),
+            Imported::Namespace => self.src.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ImportBindings {
+    bindings: HashMap<Id, ImportBinding>,
+}
+
+impl ImportBindings {
+    pub fn collect(module: &Module) -> ImportBindings {
+        let mut bindings = HashMap::new();
+
+        for item in &module.body {
+            let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item else {
+                continue;
+            };
+
+            if import_decl.type_only {
+                continue;
+            }
+
+            let src = import_decl.src.value.to_string_lossy().to_string();
+
+            for specifier in &import_decl.specifiers {
+                let (local, imported) = match specifier {
+                    ImportSpecifier::Default(s) => (&s.local, Imported::Default),
+                    ImportSpecifier::Namespace(s) => (&s.local, Imported::Namespace),
+                    ImportSpecifier::Named(s) => {
+                        if s.is_type_only {
+                            continue;
+                        }
+
+                        let name = match &s.imported {
+                            Some(ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+                            Some(ModuleExportName::Str(s)) => s.value.to_string_lossy().to_string(),
+                            None => s.local.sym.to_string(),
+                        };
+
+                        (&s.local, Imported::Named(name))
+                    }
+                };
+
+                bindings.insert(
+                    local.to_id(),
+                    ImportBinding {
+                        src: src.clone(),
+                        imported,
+                    },
+                );
+            }
+        }
+
+        ImportBindings { bindings }
+    }
+
+    pub fn get(&self, id: &Id) -> Option<&ImportBinding> {
+        self.bindings.get(id)
+    }
+
+    /// `b()` => `x#a` for `import { a as b } from "x"`,
+    /// `ns.a()` => `x#a` for `import * as ns from "x"`
+    pub fn callee_path(&self, callee: &Expr) -> Option<String> {
+        match callee.unwrap_parens() {
+            Expr::Ident(ident) => self.get(&ident.to_id()).map(|binding| binding.path()),
+            Expr::Member(member_expr) => {
+                let (Expr::Ident(obj), MemberProp::Ident(prop)) =
+                    (member_expr.obj.unwrap_parens(), &member_expr.prop)
+                else {
+                    return None;
+                };
+
+                match self.get(&obj.to_id()) {
+                    Some(binding) if binding.imported == Imported::Namespace => {
+                        Some(format!("{}#{}", binding.src, prop.sym))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// `b.c()` => `a.c` for `import { a as b } from "x"`,
+    /// `ns.a()` => `a` for `import * as ns from "x"`,
+    /// `None` for callees not imported or default imports
+    pub fn imported_name_path(&self, callee: &Expr) -> Option<String> {
+        let root = match callee.unwrap_parens() {
+            Expr::Ident(ident) => ident.to_id(),
+            Expr::Member(member_expr) => member_root(member_expr)?,
+            _ => return None,
+        };
+
+        let path = callee_path(callee)?;
+        let tail = path.split_once('.').map(|(_, tail)| tail);
+
+        match (&self.get(&root)?.imported, tail) {
+            (Imported::Named(name), Some(tail)) => Some(format!("{}.{}", name, tail)),
+            (Imported::Named(name), None) => Some(name.clone()),
+            (Imported::Namespace, Some(tail)) => Some(tail.to_string()),
+            _ => None,
+        }
+    }
+}
+
+/// Matcher of `module#export` paths of imported callees, shared by `impureCallees`, `pureImports`
+/// and `pureCallees`: `x` matches all exports of module `x`, `x#a` only matches export `a` of
+/// module `x` whatever it's renamed to.
+pub fn matches_import(patterns: &[Pattern], path: &str) -> bool {
+    if matches_any(patterns, path) {
+        return true;
+    }
+
+    match path.rsplit_once('#') {
+        Some((src, _)) => matches_any(patterns, src),
+        None => false,
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::{HashMap, HashSet};
+
+use swc_core::common::comments::{Comment, CommentKind, Comments};
+use swc_core::common::BytePos;
+use swc_core::ecma::ast::Expr;
+use swc_core::ecma::visit::{noop_visit_type, Visit, VisitWith};
+
+use super::callee::pure_target;
+
+/// `/*#__PURE__*/`, `/*@__PURE__*/` or with spaces like `/* #__PURE__ */`
+pub fn is_pure_marker(comment: &Comment) -> bool {
+    comment.kind == CommentKind::Block
+        && matches!(
+            comment
+                .text
+                .trim_matches(|c: char| c == '*' || c.is_whitespace()),
+            "#__PURE__" | "@__PURE__"
+        )
+}
+
+/// Move pure markers before wrapping parens like `/*@__PURE__*/ (fn())` to the call,
+/// and keep only one `/*#__PURE__*/` for each call, which minifiers honor.
+pub fn normalize_pure_markers<N, C>(node: &N, comments: &C)
+where
+    N: VisitWith<PureTargets>,
+    C: Comments,
+{
+    let mut targets = PureTargets::default();
+    node.visit_with(&mut targets);
+
+    for (pos, parens) in &targets.parens {
+        // `(fn)()` starts with paren of callee, the marker belongs to the outer call
+        let parens = parens
+            .iter()
+            .filter(|paren| !targets.positions.contains(paren));
+
+        let mut found = false;
+
+        for at in [pos].into_iter().chain(parens) {
+            found |= take_pure_markers(comments, *at);
+        }
+
+        if found {
+            comments.add_pure_comment(*pos);
+        }
+    }
+}
+
+// markers at `pos` taken, others kept in order
+fn take_pure_markers<C: Comments>(comments: &C, pos: BytePos) -> bool {
+    let Some(leading) = comments.take_leading(pos) else {
+        return false;
+    };
+
+    let (markers, others): (Vec<_>, Vec<_>) = leading.into_iter().partition(is_pure_marker);
+
+    if !others.is_empty() {
+        comments.add_leading_comments(pos, others);
+    }
+
+    !markers.is_empty()
+}
+
+/// Positions of calls which could be annotated, with positions of wrapping parens.
+#[derive(Default)]
+pub struct PureTargets {
+    positions: HashSet<BytePos>,
+    parens: HashMap<BytePos, Vec<BytePos>>,
+}
+
+impl Visit for PureTargets {
+    noop_visit_type!();
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let Expr::Paren(paren_expr) = expr {
+            if let Some((pos, _)) = pure_target(&paren_expr.expr) {
+                self.parens.entry(pos).or_default().push(paren_expr.span.lo);
+            }
+        } else if let Some((pos, _)) = pure_target(expr) {
+            self.positions.insert(pos);
+            self.parens.entry(pos).or_default();
+        }
+
+        expr.visit_children_with(self);
+    }
+}
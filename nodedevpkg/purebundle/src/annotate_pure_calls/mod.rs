@@ -1,23 +1,304 @@
+use serde::Deserialize;
 use swc_core::common::comments::Comments;
+use swc_core::common::Spanned;
 use swc_core::ecma::ast::{
-    ArrayLit, AssignExpr, AssignProp, CallExpr, Expr, Ident, KeyValueProp, Pass, Pat, VarDeclarator,
+    ArrayLit, ArrowExpr, AssignExpr, AssignProp, AssignTarget, BinaryOp, CallExpr, ClassProp,
+    ExportDefaultExpr, Expr, ExprStmt, Function, Id, Ident, KeyValueProp, Module, Pass,
+    PrivateProp, Program, SimpleAssignTarget, TsParamProp, TsParamPropParam, VarDeclarator,
 };
-use swc_core::ecma::visit::{noop_visit_mut_type, visit_mut_pass, VisitMut, VisitMutWith};
+use swc_core::ecma::utils::find_pat_ids;
+use swc_core::ecma::visit::{
+    noop_visit_mut_type, noop_visit_type, visit_mut_pass, Visit, VisitMut, VisitMutWith, VisitWith,
+};
+
+use self::callee::{callee_path, matches_callee, member_root, pure_target, IMPURE_CALLEES};
+use self::imports::{matches_import, ImportBindings};
+use self::markers::normalize_pure_markers;
+use self::purity::Purity;
+use crate::pattern::{matches_any, Pattern};
+use crate::report::report;
+
+mod callee;
+pub(crate) mod imports;
+mod markers;
+pub(crate) mod purity;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Mode {
+    /// all calls in value position are pure
+    #[default]
+    Aggressive,
+    /// only calls of provably pure functions defined in module, pure imports or built-ins are pure
+    Sound,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct AnnotatePureCallsConfig {
+    pub enabled: bool,
+    /// callees never annotated, extends the built-in list of timers, listeners, `console.*` and so on.
+    /// `setTimeout` matches `window.setTimeout` too, imported ones match as `module#export` by `matches_import`.
+    pub impure_callees: Vec<Pattern>,
+    pub mode: Mode,
+    /// imports treated as pure in sound mode, matched as `module#export` by `matches_import`.
+    pub pure_imports: Vec<Pattern>,
+    /// callees annotated even in statement position where results are unused, and pure in sound mode,
+    /// like `Object.freeze`. Imported ones match by imported name too, `markRaw` matches `raw` of
+    /// `import { markRaw as raw } from "vue"`, and as `module#export` by `matches_import`.
+    /// Calls of them could be dropped when unused, so only list ones whose side effects are not wanted.
+    pub pure_callees: Vec<Pattern>,
+}
+
+impl Default for AnnotatePureCallsConfig {
+    fn default() -> Self {
+        AnnotatePureCallsConfig {
+            enabled: true,
+            impure_callees: vec![],
+            mode: Mode::Aggressive,
+            pure_imports: vec![],
+            pure_callees: vec![],
+        }
+    }
+}
 
-pub fn annotate_pure_calls<C>(comments: C) -> impl VisitMut + Pass
+pub fn annotate_pure_calls<C>(comments: C, config: &AnnotatePureCallsConfig) -> impl VisitMut + Pass
 where
     C: Comments + Clone,
 {
     visit_mut_pass(PureAnnotation {
         comments: Some(comments),
+        impure_callees: impure_callees(config),
+        mode: config.mode,
+        pure_imports: config.pure_imports.clone(),
+        pure_callees: config.pure_callees.clone(),
+        imports: ImportBindings::default(),
+        purity: None,
+        targets: vec![],
     })
 }
 
+/// built-in impure callees with configured ones
+pub(crate) fn impure_callees(config: &AnnotatePureCallsConfig) -> Vec<Pattern> {
+    IMPURE_CALLEES
+        .iter()
+        .map(|p| Pattern::try_from(p.to_string()).unwrap())
+        .chain(config.impure_callees.iter().cloned())
+        .collect()
+}
+
 struct PureAnnotation<C: Comments>
 where
     C: Comments + Clone,
 {
     comments: Option<C>,
+    impure_callees: Vec<Pattern>,
+    mode: Mode,
+    pure_imports: Vec<Pattern>,
+    pure_callees: Vec<Pattern>,
+    imports: ImportBindings,
+    purity: Option<Purity>,
+    // bindings assigned by enclosing declarators or assignments
+    targets: Vec<Id>,
+}
+
+impl<C> PureAnnotation<C>
+where
+    C: Comments + Clone,
+{
+    fn annotate(&self, expr: &Expr) {
+        let mut values = vec![];
+        collect_values(expr, &mut values);
+
+        for value in values {
+            self.annotate_call(value);
+        }
+    }
+
+    // `/*#__PURE__*/` for call, new, optional call or tagged template
+    fn annotate_call(&self, expr: &Expr) {
+        let Some((pos, callees)) = pure_target(expr) else {
+            return;
+        };
+
+        if callees.iter().any(|callee| self.is_impure_callee(callee)) {
+            return;
+        }
+
+        if self.is_target_used(expr) {
+            return;
+        }
+
+        if self.mode == Mode::Sound
+            && !callees
+                .first()
+                .is_some_and(|callee| self.is_pure_callee(callee))
+            && !self
+                .purity
+                .as_ref()
+                .is_some_and(|purity| purity.is_pure_call(expr))
+        {
+            return;
+        }
+
+        if let Some(comments) = &self.comments {
+            if comments.has_flag(pos, "PURE") {
+                return;
+            }
+
+            comments.add_pure_comment(pos);
+
+            let reason = match self.mode {
+                Mode::Aggressive => "aggressive",
+                Mode::Sound => "sound",
+            };
+
+            report("annotatePureCalls", reason, expr.span(), || {
+                callees
+                    .first()
+                    .and_then(|callee| callee_path(callee))
+                    .unwrap_or_else(|| "<expr>".into())
+            });
+        };
+    }
+
+    // `Object.freeze(x);` and other configured calls in statement position
+    fn annotate_unused(&self, expr: &Expr) {
+        let Some((pos, callees)) = pure_target(expr) else {
+            return;
+        };
+
+        let Some(callee) = callees.first().filter(|callee| self.is_pure_callee(callee)) else {
+            return;
+        };
+
+        if let Some(comments) = &self.comments {
+            if comments.has_flag(pos, "PURE") {
+                return;
+            }
+
+            comments.add_pure_comment(pos);
+
+            report("annotatePureCalls", "pure callee", expr.span(), || {
+                callee_path(callee).unwrap_or_else(|| "<expr>".into())
+            });
+        }
+    }
+
+    fn is_pure_callee(&self, callee: &Expr) -> bool {
+        callee_path(callee)
+            .into_iter()
+            .chain(self.imports.imported_name_path(callee))
+            .any(|path| matches_any(&self.pure_callees, &path))
+            || self
+                .imports
+                .callee_path(callee)
+                .is_some_and(|path| matches_import(&self.pure_callees, &path))
+    }
+
+    fn is_impure_callee(&self, callee: &Expr) -> bool {
+        is_impure_callee(&self.impure_callees, &self.imports, callee)
+    }
+
+    // const a = setInterval(() => { console.log(a) }, 1000)
+    fn is_target_used(&self, expr: &Expr) -> bool {
+        references(expr, &self.targets)
+    }
+
+    fn with_targets<F>(&mut self, targets: Vec<Id>, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let len = self.targets.len();
+        self.targets.extend(targets);
+        f(self);
+        self.targets.truncate(len);
+    }
+}
+
+/// Callee matched by `impure_callees` with its path or `module#export` when imported
+pub(crate) fn is_impure_callee(
+    impure_callees: &[Pattern],
+    imports: &ImportBindings,
+    callee: &Expr,
+) -> bool {
+    if let Some(path) = imports.callee_path(callee) {
+        if matches_import(impure_callees, &path) {
+            return true;
+        }
+    }
+
+    match callee_path(callee) {
+        Some(path) => matches_callee(impure_callees, &path),
+        None => false,
+    }
+}
+
+/// Any call of impure callees evaluated by `node`, calls in nested functions are not evaluated here
+pub(crate) fn contains_impure_call<N>(
+    node: &N,
+    impure_callees: &[Pattern],
+    imports: &ImportBindings,
+) -> bool
+where
+    N: for<'a> VisitWith<ImpureCallFinder<'a>> + ?Sized,
+{
+    let mut finder = ImpureCallFinder {
+        impure_callees,
+        imports,
+        found: false,
+    };
+    node.visit_with(&mut finder);
+    finder.found
+}
+
+/// `expr` references any of `ids`
+pub(crate) fn references(expr: &Expr, ids: &[Id]) -> bool {
+    if ids.is_empty() {
+        return false;
+    }
+
+    let mut visitor = InitUsedVisit {
+        idents: ids,
+        used: false,
+    };
+    expr.visit_with(&mut visitor);
+
+    visitor.used
+}
+
+// expressions as the value of
+// `a ? x : y`, `a || x`, `a ?? x`, `(a, x)` and TS wrappers like `x as T`, `x satisfies T`, `x!`
+fn collect_values<'a>(expr: &'a Expr, values: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::Paren(paren_expr) => collect_values(&paren_expr.expr, values),
+        Expr::Cond(cond_expr) => {
+            collect_values(&cond_expr.cons, values);
+            collect_values(&cond_expr.alt, values);
+        }
+        Expr::Bin(bin_expr)
+            if matches!(
+                bin_expr.op,
+                BinaryOp::LogicalAnd | BinaryOp::LogicalOr | BinaryOp::NullishCoalescing
+            ) =>
+        {
+            collect_values(&bin_expr.left, values);
+            collect_values(&bin_expr.right, values);
+        }
+        Expr::Seq(seq_expr) => {
+            if let Some(last) = seq_expr.exprs.last() {
+                collect_values(last, values);
+            }
+        }
+        Expr::TsAs(ts_as_expr) => collect_values(&ts_as_expr.expr, values),
+        Expr::TsSatisfies(ts_satisfies_expr) => collect_values(&ts_satisfies_expr.expr, values),
+        Expr::TsNonNull(ts_non_null_expr) => collect_values(&ts_non_null_expr.expr, values),
+        Expr::TsTypeAssertion(ts_type_assertion) => collect_values(&ts_type_assertion.expr, values),
+        Expr::TsConstAssertion(ts_const_assertion) => {
+            collect_values(&ts_const_assertion.expr, values)
+        }
+        _ => values.push(expr),
+    }
 }
 
 impl<C> VisitMut for PureAnnotation<C>
@@ -26,96 +307,764 @@ where
 {
     noop_visit_mut_type!();
 
+    // existing markers are checked by `has_flag` at the position of call
+    fn visit_mut_program(&mut self, program: &mut Program) {
+        if let Some(comments) = &self.comments {
+            normalize_pure_markers(&*program, comments);
+        }
+
+        program.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_module(&mut self, module: &mut Module) {
+        // local ident with its syntax context to `module#export`
+        self.imports = ImportBindings::collect(module);
+
+        if self.mode == Mode::Sound {
+            self.purity = Some(Purity::new(
+                module,
+                self.pure_imports.clone(),
+                self.impure_callees.clone(),
+            ));
+        }
+
+        module.visit_mut_children_with(self);
+    }
+
+    // enclosing targets are assigned already when function called
+    fn visit_mut_function(&mut self, function: &mut Function) {
+        let targets = std::mem::take(&mut self.targets);
+        function.visit_mut_children_with(self);
+        self.targets = targets;
+    }
+
+    fn visit_mut_arrow_expr(&mut self, arrow_expr: &mut ArrowExpr) {
+        let targets = std::mem::take(&mut self.targets);
+        arrow_expr.visit_mut_children_with(self);
+        self.targets = targets;
+    }
+
     // x = pureCall()
     // a.x = pureCall()
+    // x = new Pure()
     fn visit_mut_assign_expr(&mut self, expr: &mut AssignExpr) {
-        if let Expr::Call(call_expr) = expr.right.unwrap_parens() {
-            if let Some(comments) = &self.comments {
-                comments.add_pure_comment(call_expr.span.lo);
-            };
-        }
-        expr.visit_mut_children_with(self);
+        let targets = match &expr.left {
+            AssignTarget::Simple(SimpleAssignTarget::Ident(ident)) => vec![ident.to_id()],
+            AssignTarget::Simple(SimpleAssignTarget::Member(member_expr)) => {
+                member_root(member_expr).into_iter().collect()
+            }
+            AssignTarget::Simple(_) => vec![],
+            AssignTarget::Pat(pat) => find_pat_ids(pat),
+        };
+
+        self.with_targets(targets, |v| {
+            v.annotate(&expr.right);
+            expr.visit_mut_children_with(v);
+        });
     }
 
+    // ({ x = pureCall() } = {})
     fn visit_mut_assign_prop(&mut self, expr: &mut AssignProp) {
-        if let Expr::Call(call_expr) = expr.value.unwrap_parens() {
-            if let Some(comments) = &self.comments {
-                comments.add_pure_comment(call_expr.span.lo);
-            };
-        }
+        self.with_targets(vec![expr.key.to_id()], |v| {
+            v.annotate(&expr.value);
+            expr.visit_mut_children_with(v);
+        });
+    }
+
+    // export default pureCall()
+    fn visit_mut_export_default_expr(&mut self, expr: &mut ExportDefaultExpr) {
+        self.annotate(&expr.expr);
         expr.visit_mut_children_with(self);
     }
 
+    // class X { static x = pureCall() }
+    fn visit_mut_class_prop(&mut self, prop: &mut ClassProp) {
+        if let Some(value) = &prop.value {
+            self.annotate(value);
+        }
+        prop.visit_mut_children_with(self);
+    }
+
+    // class X { #x = pureCall() }
+    fn visit_mut_private_prop(&mut self, prop: &mut PrivateProp) {
+        if let Some(value) = &prop.value {
+            self.annotate(value);
+        }
+        prop.visit_mut_children_with(self);
+    }
+
+    // class X { constructor(private x = pureCall()) {} }
+    fn visit_mut_ts_param_prop(&mut self, prop: &mut TsParamProp) {
+        if let TsParamPropParam::Assign(assign_pat) = &prop.param {
+            self.annotate(&assign_pat.right);
+        }
+        prop.visit_mut_children_with(self);
+    }
+
     fn visit_mut_array_lit(&mut self, expr: &mut ArrayLit) {
-        for elem in &expr.elems {
-            if let Some(elem) = &elem {
-                if let Expr::Call(call_expr) = &elem.expr.unwrap_parens() {
-                    if let Some(comments) = &self.comments {
-                        comments.add_pure_comment(call_expr.span.lo);
-                    };
-                }
-            }
+        for elem in expr.elems.iter().flatten() {
+            self.annotate(&elem.expr);
         }
         expr.visit_mut_children_with(self);
     }
 
     fn visit_mut_key_value_prop(&mut self, expr: &mut KeyValueProp) {
-        if let Expr::Call(call_expr) = expr.value.unwrap_parens() {
-            if let Some(comments) = &self.comments {
-                comments.add_pure_comment(call_expr.span.lo);
-            };
-        }
+        self.annotate(&expr.value);
         expr.visit_mut_children_with(self);
     }
 
+    // const x = pureCall()
+    // const { a, b } = pureCall()
+    // const [a, b] = pureCall()
     fn visit_mut_var_declarator(&mut self, expr: &mut VarDeclarator) {
-        if let Pat::Ident(ref mut ident) = expr.name {
-            let mut folder = InitUsedVisit {
-                ident: ident.id.clone(),
-                used: false,
-            };
-            // visit child
-            expr.init.visit_mut_children_with(&mut folder);
-
-            if !folder.used {
-                if let Some(init) = &expr.init {
-                    if let Expr::Call(call_expr) = init.unwrap_parens() {
-                        if let Some(comments) = &self.comments {
-                            comments.add_pure_comment(call_expr.span.lo);
-                        };
-                    }
+        self.with_targets(find_pat_ids(&expr.name), |v| {
+            if let Some(init) = &expr.init {
+                v.annotate(init);
+            }
+            expr.visit_mut_children_with(v);
+        });
+    }
+
+    // Object.freeze(x);
+    // a ? markRaw(x) : markRaw(y), markRaw(z);
+    fn visit_mut_expr_stmt(&mut self, stmt: &mut ExprStmt) {
+        let mut values = vec![];
+
+        match &*stmt.expr {
+            Expr::Seq(seq_expr) => {
+                for expr in &seq_expr.exprs {
+                    collect_values(expr, &mut values);
                 }
             }
+            expr => collect_values(expr, &mut values),
         }
 
-        expr.visit_mut_children_with(self);
+        for value in values {
+            self.annotate_unused(value);
+        }
+
+        stmt.visit_mut_children_with(self);
     }
 
     fn visit_mut_call_expr(&mut self, expr: &mut CallExpr) {
         for arg in &expr.args {
-            if let Expr::Call(call_expr) = arg.expr.unwrap_parens() {
-                if let Some(comments) = &self.comments {
-                    comments.add_pure_comment(call_expr.span.lo);
-                };
-            }
+            self.annotate(&arg.expr);
         }
         expr.visit_mut_children_with(self);
     }
 }
 
-struct InitUsedVisit {
-    ident: Ident,
-    used: bool,
+pub(crate) struct ImpureCallFinder<'a> {
+    impure_callees: &'a [Pattern],
+    imports: &'a ImportBindings,
+    found: bool,
 }
 
-impl VisitMut for InitUsedVisit {
-    noop_visit_mut_type!();
+impl Visit for ImpureCallFinder<'_> {
+    noop_visit_type!();
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
 
-    fn visit_mut_ident(&mut self, ident: &mut Ident) {
-        if ident.sym == self.ident.sym {
-            if ident.ctxt == self.ident.ctxt {
-                self.used = true
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let Some((_, callees)) = pure_target(expr) {
+            if callees
+                .iter()
+                .any(|callee| is_impure_callee(self.impure_callees, self.imports, callee))
+            {
+                self.found = true;
+                return;
             }
         }
+
+        expr.visit_children_with(self);
+    }
+}
+
+struct InitUsedVisit<'a> {
+    idents: &'a [Id],
+    used: bool,
+}
+
+impl Visit for InitUsedVisit<'_> {
+    noop_visit_type!();
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if self.idents.contains(&ident.to_id()) {
+            self.used = true
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::testing::assert_transform;
+
+    use super::{annotate_pure_calls, AnnotatePureCallsConfig, Mode};
+    use crate::pattern::Pattern;
+
+    #[test]
+    fn call_in_value_position_should_be_annotated() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            export const a = fn(fn());
+            b = fn();
+            const c = [fn()], d = { x: fn() };
+            fn();
+            "#,
+            r#"
+            export const a = /*#__PURE__*/ fn(/*#__PURE__*/ fn());
+            b = /*#__PURE__*/ fn();
+            const c = [/*#__PURE__*/ fn()], d = { x: /*#__PURE__*/ fn() };
+            fn();
+            "#,
+        );
+    }
+
+    #[test]
+    fn new_in_value_position_should_be_annotated() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            const cache = new Map();
+            export const bus = new EventEmitter();
+            b = new Set();
+            const c = [new Map()], d = { x: new Map() };
+            const e = fn(new Map());
+            new Worker();
+            "#,
+            r#"
+            const cache = /*#__PURE__*/ new Map();
+            export const bus = /*#__PURE__*/ new EventEmitter();
+            b = /*#__PURE__*/ new Set();
+            const c = [/*#__PURE__*/ new Map()], d = { x: /*#__PURE__*/ new Map() };
+            const e = /*#__PURE__*/ fn(/*#__PURE__*/ new Map());
+            new Worker();
+            "#,
+        );
+    }
+
+    #[test]
+    fn new_with_self_reference_should_not_be_annotated() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            const observer = new MutationObserver(() => observer.disconnect());
+            "#,
+            r#"
+            const observer = new MutationObserver(() => observer.disconnect());
+            "#,
+        );
+    }
+
+    #[test]
+    fn destructuring_declarator_should_be_annotated() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            const { a, b: { c } } = createStore();
+            const [state, setState] = useThing();
+            const { d = 1, ...e } = new Config();
+            "#,
+            r#"
+            const { a, b: { c } } = /*#__PURE__*/ createStore();
+            const [state, setState] = /*#__PURE__*/ useThing();
+            const { d = 1, ...e } = /*#__PURE__*/ new Config();
+            "#,
+        );
+    }
+
+    #[test]
+    fn destructuring_declarator_with_self_reference_should_not_be_annotated() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            const { a, b: { c } } = create(() => c);
+            const [state, setState] = useThing(() => setState(1));
+            const { d, ...e } = create(() => e);
+            "#,
+            r#"
+            const { a, b: { c } } = create(() => c);
+            const [state, setState] = useThing(() => setState(1));
+            const { d, ...e } = create(() => e);
+            "#,
+        );
+    }
+
+    #[test]
+    fn assignment_with_self_reference_should_not_be_annotated() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            let a, b, c;
+            a = poll(() => console.log(a));
+            b.timer = poll(() => b.tick());
+            [c] = poll(() => c);
+            ({ d = poll(() => d) } = {});
+            a = create();
+            b.x = create();
+            "#,
+            r#"
+            let a, b, c;
+            a = poll(() => console.log(a));
+            b.timer = poll(() => b.tick());
+            [c] = poll(() => c);
+            ({ d = poll(() => d) } = {});
+            a = /*#__PURE__*/ create();
+            b.x = /*#__PURE__*/ create();
+            "#,
+        );
+    }
+
+    #[test]
+    fn nested_value_with_self_reference_should_not_be_annotated() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            const obj = { timer: poll(() => obj.tick()), x: create() };
+            const list = [poll(() => list.pop()), create()];
+            const f = () => { const g = create(() => f) };
+            "#,
+            r#"
+            const obj = { timer: poll(() => obj.tick()), x: /*#__PURE__*/ create() };
+            const list = [poll(() => list.pop()), /*#__PURE__*/ create()];
+            const f = () => { const g = /*#__PURE__*/ create(() => f) };
+            "#,
+        );
+    }
+
+    #[test]
+    fn tagged_template_optional_call_and_chain_should_be_annotated() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            export const Button = styled.div`color: red;`;
+            export const style = css`color: red;`;
+            export const a = api?.create();
+            export const b = builder().with(x).build();
+            export const c = api?.builder().build();
+            export const d = fetch("/api").then((r) => r.json());
+            export const e = window.setTimeout(f).unref?.();
+            "#,
+            r#"
+            export const Button = /*#__PURE__*/ styled.div`color: red;`;
+            export const style = /*#__PURE__*/ css`color: red;`;
+            export const a = /*#__PURE__*/ api?.create();
+            export const b = /*#__PURE__*/ builder().with(x).build();
+            export const c = /*#__PURE__*/ api?.builder().build();
+            export const d = fetch("/api").then((r) => r.json());
+            export const e = window.setTimeout(f).unref?.();
+            "#,
+        );
+    }
+
+    #[test]
+    fn call_in_conditional_logical_sequence_and_ts_wrapper_should_be_annotated() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            const a = isDev ? createDevStore() : createStore();
+            export const b = cached ?? compute();
+            export const c = enabled && create() || fallback();
+            export const d = (init(), create());
+            export const e = create() as Store;
+            export const f = create() satisfies Store;
+            export const g = create()!;
+            export const h = await create();
+            export const i = isDev ? setTimeout(f) : create();
+            export const j = x + create();
+            "#,
+            r#"
+            const a = isDev ? /*#__PURE__*/ createDevStore() : /*#__PURE__*/ createStore();
+            export const b = cached ?? /*#__PURE__*/ compute();
+            export const c = enabled && /*#__PURE__*/ create() || /*#__PURE__*/ fallback();
+            export const d = (init(), /*#__PURE__*/ create());
+            export const e = /*#__PURE__*/ create() as Store;
+            export const f = /*#__PURE__*/ create() satisfies Store;
+            export const g = /*#__PURE__*/ create()!;
+            export const h = await create();
+            export const i = isDev ? setTimeout(f) : /*#__PURE__*/ create();
+            export const j = x + create();
+            "#,
+        );
+    }
+
+    #[test]
+    fn export_default_and_class_fields_should_be_annotated() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            class Store {
+                static instance = create();
+                static timer = setInterval(tick);
+                state = new Map();
+                #cache = new Map();
+                constructor(private client = createClient(), name) {}
+            }
+            export default defineConfig({ x: 1 });
+            "#,
+            r#"
+            class Store {
+                static instance = /*#__PURE__*/ create();
+                static timer = setInterval(tick);
+                state = /*#__PURE__*/ new Map();
+                #cache = /*#__PURE__*/ new Map();
+                constructor(private client = /*#__PURE__*/ createClient(), name) {}
+            }
+            export default /*#__PURE__*/ defineConfig({ x: 1 });
+            "#,
+        );
+    }
+
+    #[test]
+    fn impure_callees_should_not_be_annotated() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            const t = setTimeout(init, 0);
+            const off = window.addEventListener("load", init);
+            const p = fetch("/api");
+            const l = console.log("x");
+            const e = customElements.define("x-el", El);
+            const i = window.setInterval(init, 1000);
+            "#,
+            r#"
+            const t = setTimeout(init, 0);
+            const off = window.addEventListener("load", init);
+            const p = fetch("/api");
+            const l = console.log("x");
+            const e = customElements.define("x-el", El);
+            const i = window.setInterval(init, 1000);
+            "#,
+        );
+    }
+
+    #[test]
+    fn impure_callees_could_be_extended() {
+        assert_transform(
+            |comments| {
+                annotate_pure_calls(
+                    comments,
+                    &AnnotatePureCallsConfig {
+                        impure_callees: vec![
+                            Pattern::try_from("track".to_string()).unwrap(),
+                            Pattern::try_from("/^store\\.(set|delete)$/".to_string()).unwrap(),
+                        ],
+                        ..Default::default()
+                    },
+                )
+            },
+            r#"
+            const a = track("x");
+            const b = analytics.track("x");
+            const c = store.set("x");
+            const d = store.get("x");
+            "#,
+            r#"
+            const a = track("x");
+            const b = analytics.track("x");
+            const c = store.set("x");
+            const d = /*#__PURE__*/ store.get("x");
+            "#,
+        );
+    }
+
+    #[test]
+    fn only_provably_pure_calls_should_be_annotated_in_sound_mode() {
+        assert_transform(
+            |comments| {
+                annotate_pure_calls(
+                    comments,
+                    &AnnotatePureCallsConfig {
+                        mode: Mode::Sound,
+                        pure_imports: vec![
+                            Pattern::try_from("@innoai-tech/lodash#get".to_string()).unwrap(),
+                            Pattern::try_from("vue#computed".to_string()).unwrap(),
+                        ],
+                        ..Default::default()
+                    },
+                )
+            },
+            r#"
+            import { get } from "@innoai-tech/lodash";
+            import { computed, ref as r } from "vue";
+            let count = 0;
+            const add = (x, y) => x + y;
+            function sum(...values) {
+                let total = 0;
+                for (const v of values) {
+                    total = add(total, v);
+                }
+                return Math.max(total, 0);
+            }
+            function fib(n) {
+                return n < 2 ? n : fib(n - 1) + fib(n - 2);
+            }
+            const inc = () => count++;
+            const log = (x) => console.info(x);
+            const readGlobal = () => window.location;
+            const mutate = (o) => { o.x = 1; return o };
+            const create = () => { const o = {}; o.x = 1; return o };
+            export const a = add(1, 2);
+            export const b = sum(1, 2);
+            export const c = fib(10);
+            export const d = inc();
+            export const e = log(1);
+            export const f = readGlobal();
+            export const g = mutate({});
+            export const h = create();
+            export const i = get({}, "x");
+            export const j = computed(() => 1);
+            export const k = r(1);
+            export const l = unknown();
+            export const m = (() => add(1, 2))();
+            export const n = new Map();
+            export const o = new Worker();
+            "#,
+            r#"
+            import { get } from "@innoai-tech/lodash";
+            import { computed, ref as r } from "vue";
+            let count = 0;
+            const add = (x, y) => x + y;
+            function sum(...values) {
+                let total = 0;
+                for (const v of values) {
+                    total = add(total, v);
+                }
+                return Math.max(total, 0);
+            }
+            function fib(n) {
+                return n < 2 ? n : fib(n - 1) + fib(n - 2);
+            }
+            const inc = () => count++;
+            const log = (x) => console.info(x);
+            const readGlobal = () => window.location;
+            const mutate = (o) => { o.x = 1; return o };
+            const create = () => { const o = {}; o.x = 1; return o };
+            export const a = /*#__PURE__*/ add(1, 2);
+            export const b = /*#__PURE__*/ sum(1, 2);
+            export const c = /*#__PURE__*/ fib(10);
+            export const d = inc();
+            export const e = log(1);
+            export const f = readGlobal();
+            export const g = mutate({});
+            export const h = /*#__PURE__*/ create();
+            export const i = /*#__PURE__*/ get({}, "x");
+            export const j = /*#__PURE__*/ computed(() => 1);
+            export const k = r(1);
+            export const l = unknown();
+            export const m = /*#__PURE__*/ (() => add(1, 2))();
+            export const n = /*#__PURE__*/ new Map();
+            export const o = new Worker();
+            "#,
+        );
+    }
+
+    #[test]
+    fn pure_callees_should_be_annotated_in_statement_position() {
+        assert_transform(
+            |comments| {
+                annotate_pure_calls(
+                    comments,
+                    &AnnotatePureCallsConfig {
+                        pure_callees: vec![
+                            Pattern::try_from("Object.freeze".to_string()).unwrap(),
+                            Pattern::try_from("markRaw".to_string()).unwrap(),
+                        ],
+                        ..Default::default()
+                    },
+                )
+            },
+            r#"
+            import { markRaw as raw } from "vue";
+            import * as vue from "vue";
+            Object.freeze(config);
+            raw(a), vue.markRaw(b);
+            isDev ? markRaw(c) : Object.freeze(d);
+            registerIcons();
+            Object.assign(target, source);
+            "#,
+            r#"
+            import { markRaw as raw } from "vue";
+            import * as vue from "vue";
+            /*#__PURE__*/ Object.freeze(config);
+            /*#__PURE__*/ raw(a), /*#__PURE__*/ vue.markRaw(b);
+            isDev ? /*#__PURE__*/ markRaw(c) : /*#__PURE__*/ Object.freeze(d);
+            registerIcons();
+            Object.assign(target, source);
+            "#,
+        );
+    }
+
+    #[test]
+    fn pure_callees_should_be_pure_in_sound_mode() {
+        assert_transform(
+            |comments| {
+                annotate_pure_calls(
+                    comments,
+                    &AnnotatePureCallsConfig {
+                        mode: Mode::Sound,
+                        pure_callees: vec![Pattern::try_from("markRaw".to_string()).unwrap()],
+                        ..Default::default()
+                    },
+                )
+            },
+            r#"
+            import { markRaw } from "vue";
+            export const a = markRaw({});
+            export const b = shallowRef({});
+            "#,
+            r#"
+            import { markRaw } from "vue";
+            export const a = /*#__PURE__*/ markRaw({});
+            export const b = shallowRef({});
+            "#,
+        );
+    }
+
+    #[test]
+    fn callees_should_match_by_module_export() {
+        assert_transform(
+            |comments| {
+                annotate_pure_calls(
+                    comments,
+                    &AnnotatePureCallsConfig {
+                        impure_callees: vec![
+                            Pattern::try_from("rxjs".to_string()).unwrap(),
+                            Pattern::try_from("vue#watch".to_string()).unwrap(),
+                        ],
+                        pure_callees: vec![
+                            Pattern::try_from("ramda".to_string()).unwrap()
+                        ],
+                        ..Default::default()
+                    },
+                )
+            },
+            r#"
+            import { interval } from "rxjs";
+            import * as rx from "rxjs";
+            import { watch as w, computed } from "vue";
+            import { assoc } from "ramda";
+            export const a = interval(1000).pipe(take(1));
+            export const b = rx.timer(1);
+            export const c = w(source, cb);
+            export const d = computed(() => 1);
+            assoc("x", 1, obj);
+            "#,
+            r#"
+            import { interval } from "rxjs";
+            import * as rx from "rxjs";
+            import { watch as w, computed } from "vue";
+            import { assoc } from "ramda";
+            export const a = interval(1000).pipe(/*#__PURE__*/ take(1));
+            export const b = rx.timer(1);
+            export const c = w(source, cb);
+            export const d = /*#__PURE__*/ computed(() => 1);
+            /*#__PURE__*/ assoc("x", 1, obj);
+            "#,
+        );
+    }
+
+    #[test]
+    fn existing_pure_markers_should_be_normalized() {
+        assert_transform(
+            |comments| annotate_pure_calls(comments, &Default::default()),
+            r#"
+            export const a = /*@__PURE__*/ create();
+            export const b = /*#__PURE__*/ (create());
+            export const c = /* #__PURE__ */ /*#__PURE__*/ create();
+            export const d = /*#__PURE__*/ (a)();
+            const e = /*@__PURE__*/ /* keep */ ((setTimeout(f)));
+            "#,
+            r#"
+            export const a = /*#__PURE__*/ create();
+            export const b = (/*#__PURE__*/ create());
+            export const c = /*#__PURE__*/ create();
+            export const d = /*#__PURE__*/ (a)();
+            const e = /* keep */ ((/*#__PURE__*/ setTimeout(f)));
+            "#,
+        );
+    }
+
+    #[test]
+    fn writes_through_aliases_patterns_or_reassigned_functions_should_be_impure_in_sound_mode() {
+        assert_transform(
+            |comments| {
+                annotate_pure_calls(
+                    comments,
+                    &AnnotatePureCallsConfig {
+                        mode: Mode::Sound,
+                        ..Default::default()
+                    },
+                )
+            },
+            r#"
+            const touch = (o) => { const p = o; p.touched = true; return p };
+            const setX = (o) => { [o.x] = [1]; return o };
+            const nested = () => { const o = { a: {} }; o.a.x = 1; return o };
+            const each = (o) => { const r = {}; for (r.x of [1]) {} for (o.x of [1]) {} return r };
+            function g() { return 1 }
+            g = () => { window.x = 1 };
+            const fresh = () => { const o = {}; [o.x] = [1]; return o };
+            export const a = touch(globalObj);
+            export const b = setX(globalObj);
+            export const c = nested();
+            export const d = each({});
+            export const e = g();
+            export const f = fresh();
+            "#,
+            r#"
+            const touch = (o) => { const p = o; p.touched = true; return p };
+            const setX = (o) => { [o.x] = [1]; return o };
+            const nested = () => { const o = { a: {} }; o.a.x = 1; return o };
+            const each = (o) => { const r = {}; for (r.x of [1]) {} for (o.x of [1]) {} return r };
+            function g() { return 1 }
+            g = () => { window.x = 1 };
+            const fresh = () => { const o = {}; [o.x] = [1]; return o };
+            export const a = touch(globalObj);
+            export const b = setX(globalObj);
+            export const c = nested();
+            export const d = each({});
+            export const e = g();
+            export const f = /*#__PURE__*/ fresh();
+            "#,
+        );
+    }
+
+    #[test]
+    fn functions_calling_impure_ones_in_cycle_should_be_impure_in_sound_mode() {
+        assert_transform(
+            |comments| {
+                annotate_pure_calls(
+                    comments,
+                    &AnnotatePureCallsConfig {
+                        mode: Mode::Sound,
+                        ..Default::default()
+                    },
+                )
+            },
+            r#"
+            function a(n) { if (n) b(n - 1); window.x = 1; return 1 }
+            function b(n) { if (n) a(n - 1); return 2 }
+            function even(n) { return n === 0 ? true : odd(n - 1) }
+            function odd(n) { return n === 0 ? false : even(n - 1) }
+            export const p = a(1);
+            export const q = b(1);
+            export const r = odd(1);
+            export const s = even(1);
+            "#,
+            r#"
+            function a(n) { if (n) b(n - 1); window.x = 1; return 1 }
+            function b(n) { if (n) a(n - 1); return 2 }
+            function even(n) { return n === 0 ? true : odd(n - 1) }
+            function odd(n) { return n === 0 ? false : even(n - 1) }
+            export const p = a(1);
+            export const q = b(1);
+            export const r = /*#__PURE__*/ odd(1);
+            export const s = /*#__PURE__*/ even(1);
+            "#,
+        );
     }
 }
@@ -0,0 +1,579 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+
+use swc_core::ecma::ast::{
+    ArrowExpr, AssignExpr, AssignTarget, BlockStmtOrExpr, Callee, Decl, DefaultDecl, Expr, ForHead,
+    ForInStmt, ForOfStmt, Function, Id, MemberExpr, Module, ModuleDecl, ModuleItem, Pat,
+    SimpleAssignTarget, Stmt, UnaryOp, UpdateExpr, VarDeclKind, VarDeclarator,
+};
+use swc_core::ecma::utils::{collect_decls, find_pat_ids};
+use swc_core::ecma::visit::{noop_visit_type, Visit, VisitWith};
+
+use super::callee::{callee_path, matches_callee, member_root};
+use super::imports::{matches_import, ImportBindings};
+use crate::pattern::Pattern;
+
+// globals safe to read
+const SAFE_GLOBALS: [&str; 20] = [
+    "undefined",
+    "NaN",
+    "Infinity",
+    "Object",
+    "Array",
+    "String",
+    "Number",
+    "Boolean",
+    "Symbol",
+    "BigInt",
+    "Math",
+    "JSON",
+    "Map",
+    "Set",
+    "WeakMap",
+    "WeakSet",
+    "Date",
+    "RegExp",
+    "Error",
+    "TypeError",
+];
+
+// globals calls without side effects
+const PURE_GLOBAL_CALLS: [&str; 16] = [
+    "String",
+    "Number",
+    "Boolean",
+    "Symbol",
+    "BigInt",
+    "parseInt",
+    "parseFloat",
+    "isNaN",
+    "isFinite",
+    "Array.isArray",
+    "Object.keys",
+    "Object.values",
+    "Object.entries",
+    "Object.create",
+    "JSON.parse",
+    "JSON.stringify",
+];
+
+// global constructors without side effects
+const PURE_GLOBAL_CONSTRUCTORS: [&str; 10] = [
+    "Object",
+    "Array",
+    "Map",
+    "Set",
+    "WeakMap",
+    "WeakSet",
+    "Date",
+    "RegExp",
+    "Error",
+    "TypeError",
+];
+
+fn is_pure_global_call(path: &str) -> bool {
+    PURE_GLOBAL_CALLS.contains(&path) || path.starts_with("Math.") || path.starts_with("Number.is")
+}
+
+#[derive(Clone)]
+enum LocalFn {
+    Function(Box<Function>),
+    Arrow(Box<ArrowExpr>),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    // depth in the stack of functions being analyzed
+    Analyzing(usize),
+    Done(bool),
+}
+
+/// Purity of functions defined in module.
+///
+/// A function is pure when it only writes its own locals, not reads globals except built-ins,
+/// and only calls pure functions, pure imports or built-ins without side effects.
+pub struct Purity {
+    decls: HashSet<Id>,
+    functions: HashMap<Id, LocalFn>,
+    imports: ImportBindings,
+    pure_imports: Vec<Pattern>,
+    impure_callees: Vec<Pattern>,
+    states: RefCell<HashMap<Id, State>>,
+    // count of functions being analyzed
+    depth: Cell<usize>,
+    // lowest depth of functions being analyzed, which were assumed pure by recursive calls
+    assumed: Cell<usize>,
+}
+
+impl Purity {
+    pub fn new(
+        module: &Module,
+        pure_imports: Vec<Pattern>,
+        impure_callees: Vec<Pattern>,
+    ) -> Purity {
+        let mut functions = HashMap::new();
+
+        for item in &module.body {
+            let decl = match item {
+                ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => &export_decl.decl,
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export_default_decl)) => {
+                    if let DefaultDecl::Fn(fn_expr) = &export_default_decl.decl {
+                        if let Some(ident) = &fn_expr.ident {
+                            functions
+                                .insert(ident.to_id(), LocalFn::Function(fn_expr.function.clone()));
+                        }
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            match decl {
+                Decl::Fn(fn_decl) => {
+                    functions.insert(
+                        fn_decl.ident.to_id(),
+                        LocalFn::Function(fn_decl.function.clone()),
+                    );
+                }
+                // only const could not be reassigned
+                Decl::Var(var_decl) if var_decl.kind == VarDeclKind::Const => {
+                    for decl in &var_decl.decls {
+                        let (Pat::Ident(ident), Some(init)) = (&decl.name, &decl.init) else {
+                            continue;
+                        };
+
+                        match init.unwrap_parens() {
+                            Expr::Fn(fn_expr) => {
+                                functions.insert(
+                                    ident.to_id(),
+                                    LocalFn::Function(fn_expr.function.clone()),
+                                );
+                            }
+                            Expr::Arrow(arrow_expr) => {
+                                functions.insert(
+                                    ident.to_id(),
+                                    LocalFn::Arrow(Box::new(arrow_expr.clone())),
+                                );
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // `function g() {}` could be reassigned by `g = () => {}` anywhere
+        let assigned = assigned_ids(module);
+        functions.retain(|id, _| !assigned.contains(id));
+
+        Purity {
+            decls: collect_decls::<Id, _>(module).into_iter().collect(),
+            functions,
+            imports: ImportBindings::collect(module),
+            pure_imports,
+            impure_callees,
+            states: Default::default(),
+            depth: Cell::new(0),
+            assumed: Cell::new(usize::MAX),
+        }
+    }
+
+    /// Call or new expression is provably without side effects.
+    pub fn is_pure_call(&self, expr: &Expr) -> bool {
+        match expr.unwrap_parens() {
+            Expr::Call(call_expr) => match &call_expr.callee {
+                Callee::Expr(callee) => self.is_pure_callee(callee),
+                _ => false,
+            },
+            Expr::New(new_expr) => self.is_pure_constructor(&new_expr.callee),
+            _ => false,
+        }
+    }
+
+    fn is_global(&self, id: &Id) -> bool {
+        !self.decls.contains(id)
+    }
+
+    fn is_pure_callee(&self, callee: &Expr) -> bool {
+        if let Some(path) = callee_path(callee) {
+            if matches_callee(&self.impure_callees, &path) {
+                return false;
+            }
+        }
+
+        if let Some(path) = self.imports.callee_path(callee) {
+            return !matches_import(&self.impure_callees, &path)
+                && matches_import(&self.pure_imports, &path);
+        }
+
+        match callee.unwrap_parens() {
+            Expr::Ident(ident) => {
+                let id = ident.to_id();
+
+                if self.is_global(&id) {
+                    return is_pure_global_call(&ident.sym);
+                }
+
+                self.is_pure_local_fn(&id)
+            }
+            Expr::Member(member_expr) => match member_root(member_expr) {
+                Some(root) if self.is_global(&root) => {
+                    callee_path(callee).is_some_and(|path| is_pure_global_call(&path))
+                }
+                _ => false,
+            },
+            // IIFE
+            Expr::Fn(fn_expr) => self.is_pure_fn(&LocalFn::Function(fn_expr.function.clone())),
+            Expr::Arrow(arrow_expr) => {
+                self.is_pure_fn(&LocalFn::Arrow(Box::new(arrow_expr.clone())))
+            }
+            _ => false,
+        }
+    }
+
+    fn is_pure_constructor(&self, callee: &Expr) -> bool {
+        match callee.unwrap_parens() {
+            Expr::Ident(ident) => {
+                self.is_global(&ident.to_id()) && PURE_GLOBAL_CONSTRUCTORS.contains(&&*ident.sym)
+            }
+            _ => false,
+        }
+    }
+
+    /// Function defined in module is pure.
+    pub fn is_pure_local_fn(&self, id: &Id) -> bool {
+        let Some(local_fn) = self.functions.get(id) else {
+            return false;
+        };
+
+        let state = self.states.borrow().get(id).copied();
+
+        match state {
+            Some(State::Done(pure)) => pure,
+            // recursive call, decided by the rest of body
+            Some(State::Analyzing(depth)) => {
+                self.assumed.set(self.assumed.get().min(depth));
+                true
+            }
+            None => {
+                let depth = self.depth.get();
+                let outer_assumed = self.assumed.replace(usize::MAX);
+
+                self.states
+                    .borrow_mut()
+                    .insert(id.clone(), State::Analyzing(depth));
+                self.depth.set(depth + 1);
+
+                let pure = self.is_pure_fn(local_fn);
+
+                self.depth.set(depth);
+                let assumed = self.assumed.get();
+
+                // pure by assuming outer functions of a cycle pure, which could be proved impure later,
+                // so only cached when the outermost function of the cycle is done
+                if !pure || assumed >= depth {
+                    self.states
+                        .borrow_mut()
+                        .insert(id.clone(), State::Done(pure));
+                } else {
+                    self.states.borrow_mut().remove(id);
+                }
+
+                self.assumed.set(if assumed < depth {
+                    outer_assumed.min(assumed)
+                } else {
+                    outer_assumed
+                });
+
+                pure
+            }
+        }
+    }
+
+    fn is_pure_fn(&self, local_fn: &LocalFn) -> bool {
+        let (params, locals, is_async_or_generator): (Vec<Id>, _, _) = match local_fn {
+            LocalFn::Function(function) => (
+                find_pat_ids::<_, Id>(&function.params),
+                collect_decls::<Id, _>(&function.body),
+                function.is_async || function.is_generator,
+            ),
+            LocalFn::Arrow(arrow_expr) => (
+                find_pat_ids::<_, Id>(&arrow_expr.params),
+                collect_decls::<Id, _>(&*arrow_expr.body),
+                arrow_expr.is_async || arrow_expr.is_generator,
+            ),
+        };
+
+        if is_async_or_generator {
+            return false;
+        }
+
+        let fresh = match local_fn {
+            LocalFn::Function(function) => fresh_locals(&function.body),
+            LocalFn::Arrow(arrow_expr) => fresh_locals(&*arrow_expr.body),
+        };
+
+        let mut visitor = FnPurityVisitor {
+            purity: self,
+            fresh,
+            locals: locals.into_iter().chain(params).collect(),
+            pure: true,
+        };
+
+        match local_fn {
+            LocalFn::Function(function) => {
+                function.params.visit_with(&mut visitor);
+                function.body.visit_with(&mut visitor);
+            }
+            LocalFn::Arrow(arrow_expr) => {
+                arrow_expr.params.visit_with(&mut visitor);
+                match &*arrow_expr.body {
+                    BlockStmtOrExpr::BlockStmt(block_stmt) => block_stmt.visit_with(&mut visitor),
+                    BlockStmtOrExpr::Expr(expr) => expr.visit_with(&mut visitor),
+                }
+            }
+        }
+
+        visitor.pure
+    }
+}
+
+/// Identifiers assigned or updated anywhere in `node`, nested functions included.
+fn assigned_ids<N: VisitWith<AssignedIds>>(node: &N) -> HashSet<Id> {
+    let mut visitor = AssignedIds::default();
+    node.visit_with(&mut visitor);
+    visitor.ids
+}
+
+#[derive(Default)]
+struct AssignedIds {
+    ids: HashSet<Id>,
+}
+
+impl Visit for AssignedIds {
+    noop_visit_type!();
+
+    fn visit_assign_expr(&mut self, assign_expr: &AssignExpr) {
+        match &assign_expr.left {
+            AssignTarget::Simple(SimpleAssignTarget::Ident(ident)) => {
+                self.ids.insert(ident.to_id());
+            }
+            AssignTarget::Pat(pat) => self.ids.extend(find_pat_ids::<_, Id>(pat)),
+            AssignTarget::Simple(_) => {}
+        }
+
+        assign_expr.visit_children_with(self);
+    }
+
+    fn visit_update_expr(&mut self, update_expr: &UpdateExpr) {
+        if let Expr::Ident(ident) = update_expr.arg.unwrap_parens() {
+            self.ids.insert(ident.to_id());
+        }
+
+        update_expr.visit_children_with(self);
+    }
+
+    fn visit_for_head(&mut self, for_head: &ForHead) {
+        if let ForHead::Pat(pat) = for_head {
+            self.ids.extend(find_pat_ids::<_, Id>(&**pat));
+        }
+
+        for_head.visit_children_with(self);
+    }
+}
+
+/// Locals holding objects created by function self, like `const o = {}`, `[]` or `new X()`,
+/// which are never reassigned. Aliases of them are not fresh.
+fn fresh_locals<N>(body: &N) -> HashSet<Id>
+where
+    N: VisitWith<FreshCandidates> + VisitWith<AssignedIds>,
+{
+    let mut candidates = FreshCandidates::default();
+    body.visit_with(&mut candidates);
+
+    let assigned = assigned_ids(body);
+
+    candidates
+        .ids
+        .into_iter()
+        .filter(|id| !assigned.contains(id))
+        .collect()
+}
+
+#[derive(Default)]
+struct FreshCandidates {
+    ids: HashSet<Id>,
+}
+
+impl Visit for FreshCandidates {
+    noop_visit_type!();
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+        if let (Pat::Ident(ident), Some(init)) = (&declarator.name, &declarator.init) {
+            if matches!(
+                init.unwrap_parens(),
+                Expr::Object(_) | Expr::Array(_) | Expr::New(_)
+            ) {
+                self.ids.insert(ident.to_id());
+            }
+        }
+
+        declarator.visit_children_with(self);
+    }
+}
+
+struct FnPurityVisitor<'a> {
+    purity: &'a Purity,
+    fresh: HashSet<Id>,
+    locals: HashSet<Id>,
+    pure: bool,
+}
+
+impl FnPurityVisitor<'_> {
+    fn check_write(&mut self, id: &Id) {
+        if !self.locals.contains(id) {
+            self.pure = false
+        }
+    }
+
+    // mutating objects only created by function self, `o.x = 1` but not `o.a.x = 1`,
+    // as `o.a` could be any object
+    fn check_member_write(&mut self, member_expr: &MemberExpr) {
+        match member_expr.obj.unwrap_parens() {
+            Expr::Ident(obj) if self.fresh.contains(&obj.to_id()) => {}
+            _ => self.pure = false,
+        }
+    }
+
+    // `[a, o.x] = value` or `for (o.x of value)`
+    fn check_pat_write(&mut self, pat: &Pat) {
+        for id in find_pat_ids::<_, Id>(pat) {
+            self.check_write(&id)
+        }
+
+        let mut targets = PatExprTargets::default();
+        pat.visit_with(&mut targets);
+
+        for expr in targets.exprs {
+            match expr.unwrap_parens() {
+                Expr::Member(member_expr) => self.check_member_write(member_expr),
+                _ => self.pure = false,
+            }
+        }
+    }
+}
+
+// expressions written by patterns, like `o.x` of `[o.x] = value`
+#[derive(Default)]
+struct PatExprTargets {
+    exprs: Vec<Expr>,
+}
+
+impl Visit for PatExprTargets {
+    noop_visit_type!();
+
+    // default values are evaluated, not written
+    fn visit_expr(&mut self, _: &Expr) {}
+
+    fn visit_pat(&mut self, pat: &Pat) {
+        if let Pat::Expr(expr) = pat {
+            self.exprs.push((**expr).clone());
+            return;
+        }
+
+        pat.visit_children_with(self);
+    }
+}
+
+impl Visit for FnPurityVisitor<'_> {
+    noop_visit_type!();
+
+    // nested functions only matter when called, and calls of them are treated as impure
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt) {
+        if let ForHead::Pat(pat) = &for_in_stmt.left {
+            self.check_pat_write(pat);
+        }
+
+        for_in_stmt.visit_children_with(self);
+    }
+
+    fn visit_for_of_stmt(&mut self, for_of_stmt: &ForOfStmt) {
+        if let ForHead::Pat(pat) = &for_of_stmt.left {
+            self.check_pat_write(pat);
+        }
+
+        for_of_stmt.visit_children_with(self);
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        if let Stmt::Throw(_) = stmt {
+            self.pure = false;
+            return;
+        }
+
+        stmt.visit_children_with(self);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        if !self.pure {
+            return;
+        }
+
+        match expr {
+            Expr::Ident(ident) => {
+                if self.purity.is_global(&ident.to_id()) && !SAFE_GLOBALS.contains(&&*ident.sym) {
+                    self.pure = false;
+                }
+            }
+            Expr::Assign(assign_expr) => {
+                match &assign_expr.left {
+                    AssignTarget::Simple(SimpleAssignTarget::Ident(ident)) => {
+                        self.check_write(&ident.to_id())
+                    }
+                    AssignTarget::Simple(SimpleAssignTarget::Member(member_expr)) => {
+                        self.check_member_write(member_expr)
+                    }
+                    AssignTarget::Simple(_) => self.pure = false,
+                    AssignTarget::Pat(pat) => self.check_pat_write(&pat.clone().into()),
+                }
+
+                assign_expr.visit_children_with(self);
+            }
+            Expr::Update(update_expr) => {
+                match update_expr.arg.unwrap_parens() {
+                    Expr::Ident(ident) => self.check_write(&ident.to_id()),
+                    Expr::Member(member_expr) => self.check_member_write(member_expr),
+                    _ => self.pure = false,
+                }
+
+                update_expr.visit_children_with(self);
+            }
+            Expr::Unary(unary_expr) if unary_expr.op == UnaryOp::Delete => self.pure = false,
+            Expr::Call(_) | Expr::New(_) => {
+                if !self.purity.is_pure_call(expr) {
+                    self.pure = false;
+                    return;
+                }
+
+                expr.visit_children_with(self);
+            }
+            Expr::TaggedTpl(_)
+            | Expr::Await(_)
+            | Expr::Yield(_)
+            | Expr::OptChain(_)
+            | Expr::MetaProp(_)
+            | Expr::SuperProp(_) => self.pure = false,
+            _ => expr.visit_children_with(self),
+        }
+    }
+}
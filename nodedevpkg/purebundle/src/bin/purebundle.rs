@@ -0,0 +1,221 @@
+use std::path::{Path, PathBuf};
+use std::{env, fs, process};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use purebundle::{transform_code, Config, TransformOptions};
+
+const USAGE: &str = "Usage: purebundle <dir> [--config <options.json>]
+
+Rewrite all .js/.mjs files in <dir> in place,
+source maps referenced by sourceMappingURL, or of <file>.map, will be chained and rewritten too.";
+
+const SOURCE_MAPPING_URL: &str = "//# sourceMappingURL=";
+
+const INLINE_SOURCE_MAP: &str = "data:application/json;charset=utf-8;base64,";
+
+/// Where the input source map is from, the output one is written to the same place.
+enum SourceMapLocation {
+    Inline,
+    File { path: PathBuf, url: String },
+}
+
+fn main() {
+    if let Err(err) = run(env::args().skip(1).collect()) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let mut dir = None;
+    let mut config = Config::default();
+
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            "--config" => {
+                let file = args.next().ok_or(USAGE)?;
+                let json = fs::read_to_string(&file).map_err(|err| format!("{}: {}", file, err))?;
+                config = Config::from_json(&json)?;
+            }
+            _ if dir.is_none() && !arg.starts_with('-') => dir = Some(PathBuf::from(arg)),
+            _ => return Err(USAGE.into()),
+        }
+    }
+
+    let dir = dir.ok_or(USAGE)?;
+
+    let mut files = vec![];
+    collect_files(&dir, &mut files)?;
+
+    for file in files {
+        process_file(&file, &config)?;
+    }
+
+    Ok(())
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|err| format!("{}: {}", dir.display(), err))?;
+
+    for entry in entries {
+        let path = entry.map_err(|err| err.to_string())?.path();
+
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("js" | "mjs")
+        ) {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+
+    Ok(())
+}
+
+fn process_file(file: &Path, config: &Config) -> Result<(), String> {
+    let source = fs::read_to_string(file).map_err(|err| format!("{}: {}", file.display(), err))?;
+
+    let (input_source_map, location) = match read_source_map(file, &source)? {
+        Some((map, location)) => (Some(map), Some(location)),
+        None => (None, None),
+    };
+
+    let output = transform_code(
+        &source,
+        &TransformOptions {
+            filename: file.display().to_string(),
+            config: config.clone(),
+            source_map: input_source_map.is_some(),
+            input_source_map,
+        },
+    )?;
+
+    for entry in &output.report {
+        println!(
+            "{}:{}:{} {} ({}) {}",
+            file.display(),
+            entry.line,
+            entry.column,
+            entry.rule,
+            entry.reason,
+            entry.text
+        );
+    }
+
+    let mut code = output.code;
+
+    if let (Some(map), Some(location)) = (output.map, location) {
+        // original comment may be printed as trailing one of the last statement
+        if let Some(i) = code.rfind(SOURCE_MAPPING_URL) {
+            code.truncate(i);
+        }
+
+        let url = match location {
+            SourceMapLocation::Inline => format!("{}{}", INLINE_SOURCE_MAP, STANDARD.encode(map)),
+            SourceMapLocation::File { path, url } => {
+                fs::write(&path, map).map_err(|err| format!("{}: {}", path.display(), err))?;
+                url
+            }
+        };
+
+        code = format!("{}\n{}{}\n", code.trim_end(), SOURCE_MAPPING_URL, url);
+    }
+
+    fs::write(file, code).map_err(|err| format!("{}: {}", file.display(), err))
+}
+
+/// Source map by `//# sourceMappingURL=` of the file first, then `<file>.map`.
+fn read_source_map(
+    file: &Path,
+    source: &str,
+) -> Result<Option<(String, SourceMapLocation)>, String> {
+    if let Some(url) = source_mapping_url(source) {
+        if url.starts_with("data:") {
+            let map = decode_data_url(url).map_err(|err| format!("{}: {}", file.display(), err))?;
+            return Ok(Some((map, SourceMapLocation::Inline)));
+        }
+
+        let path = file.parent().unwrap_or(Path::new("")).join(url);
+
+        if let Ok(map) = fs::read_to_string(&path) {
+            return Ok(Some((
+                map,
+                SourceMapLocation::File {
+                    path,
+                    url: url.to_string(),
+                },
+            )));
+        }
+    }
+
+    let path = PathBuf::from(format!("{}.map", file.display()));
+
+    Ok(fs::read_to_string(&path).ok().map(|map| {
+        let url = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        (map, SourceMapLocation::File { path, url })
+    }))
+}
+
+// url of the last `//# sourceMappingURL=`, which is the one honored by browsers
+fn source_mapping_url(source: &str) -> Option<&str> {
+    let i = source.rfind(SOURCE_MAPPING_URL)?;
+    let url = source[i + SOURCE_MAPPING_URL.len()..]
+        .lines()
+        .next()?
+        .trim();
+
+    (!url.is_empty()).then_some(url)
+}
+
+// `data:application/json;base64,...` or `data:application/json,...`
+fn decode_data_url(url: &str) -> Result<String, String> {
+    let (meta, data) = url
+        .split_once(',')
+        .ok_or_else(|| format!("invalid source map url: {}", url))?;
+
+    if !meta.ends_with(";base64") {
+        return Ok(data.to_string());
+    }
+
+    let bytes = STANDARD
+        .decode(data)
+        .map_err(|err| format!("invalid inline source map: {}", err))?;
+
+    String::from_utf8(bytes).map_err(|err| format!("invalid inline source map: {}", err))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode_data_url, source_mapping_url};
+
+    #[test]
+    fn last_source_mapping_url_should_be_used() {
+        let source = "//# sourceMappingURL=a.js.map\nexport const a = 1;\n//# sourceMappingURL=maps/a.js.map \n";
+
+        assert_eq!(source_mapping_url(source), Some("maps/a.js.map"));
+        assert_eq!(source_mapping_url("export const a = 1;\n"), None);
+    }
+
+    #[test]
+    fn inline_source_map_should_be_decoded() {
+        // `{"version":3}`
+        let url = "data:application/json;charset=utf-8;base64,eyJ2ZXJzaW9uIjozfQ==";
+
+        assert_eq!(decode_data_url(url).unwrap(), r#"{"version":3}"#);
+        assert!(decode_data_url("data:application/json;base64,%%").is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use serde::Deserialize;
+
+use crate::annotate_no_side_effects::AnnotateNoSideEffectsConfig;
+use crate::annotate_pure_calls::AnnotatePureCallsConfig;
+use crate::drop_unused_imports::DropUnusedImportsConfig;
+use crate::fold_static_props::FoldStaticPropsConfig;
+use crate::fold_ts_enums::FoldTsEnumsConfig;
+use crate::ignore_side_imports::IgnoreSideImportsConfig;
+use crate::merge_imports::MergeImportsConfig;
+use crate::strip_dev_code::StripDevCodeConfig;
+
+/// Options passed by `usePlugin({...})`
+///
+/// ```json
+/// {
+///   "stripDevCode": { "enabled": false },
+///   "ignoreSideImports": { "enabled": true },
+///   "foldStaticProps": { "enabled": false },
+///   "foldTsEnums": { "enabled": true },
+///   "annotatePureCalls": { "enabled": true },
+///   "annotateNoSideEffects": { "enabled": false },
+///   "dropUnusedImports": { "enabled": false },
+///   "mergeImports": { "enabled": false },
+///   "report": false
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct Config {
+    pub strip_dev_code: StripDevCodeConfig,
+    pub ignore_side_imports: IgnoreSideImportsConfig,
+    pub fold_static_props: FoldStaticPropsConfig,
+    pub fold_ts_enums: FoldTsEnumsConfig,
+    pub annotate_pure_calls: AnnotatePureCallsConfig,
+    pub annotate_no_side_effects: AnnotateNoSideEffectsConfig,
+    pub drop_unused_imports: DropUnusedImportsConfig,
+    pub merge_imports: MergeImportsConfig,
+    /// attach `/*#__PUREBUNDLE_REPORT__ [...]*/` with entries of every transformation to output
+    pub report: bool,
+}
+
+impl Config {
+    pub fn from_json(json: &str) -> Result<Config, String> {
+        if json.trim().is_empty() {
+            return Ok(Config::default());
+        }
+
+        serde_json::from_str(json).map_err(|err| format!("invalid purebundle options: {}", err))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Config;
+
+    #[test]
+    fn empty_options_should_enable_default_passes() {
+        for json in ["", "{}"] {
+            let config = Config::from_json(json).unwrap();
+
+            assert!(!config.strip_dev_code.enabled);
+            assert!(config.ignore_side_imports.enabled);
+            assert!(!config.fold_static_props.enabled);
+            assert!(config.fold_ts_enums.enabled);
+            assert!(config.annotate_pure_calls.enabled);
+            assert!(!config.annotate_no_side_effects.enabled);
+            assert!(!config.drop_unused_imports.enabled);
+            assert!(!config.merge_imports.enabled);
+        }
+    }
+
+    #[test]
+    fn pass_could_be_disabled() {
+        let config = Config::from_json(r#"{ "ignoreSideImports": { "enabled": false } }"#).unwrap();
+
+        assert!(!config.ignore_side_imports.enabled);
+        assert!(config.annotate_pure_calls.enabled);
+    }
+
+    #[test]
+    fn malformed_options_should_be_reported() {
+        let err = Config::from_json(r#"{ "annotatePureCall": {} }"#).unwrap_err();
+        assert!(err.contains("unknown field `annotatePureCall`"), "{}", err);
+
+        let err = Config::from_json(r#"{ "ignoreSideImports": { "enabled": "no" } }"#).unwrap_err();
+        assert!(err.contains("invalid type"), "{}", err);
+    }
+}
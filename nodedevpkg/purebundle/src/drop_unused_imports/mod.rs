@@ -0,0 +1,220 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use swc_core::common::Spanned;
+use swc_core::ecma::ast::{
+    Id, Ident, ImportDecl, ImportNamedSpecifier, ImportSpecifier, Module, ModuleDecl,
+    ModuleExportName, ModuleItem, Pass,
+};
+use swc_core::ecma::visit::{
+    noop_visit_mut_type, noop_visit_type, visit_mut_pass, Visit, VisitMut, VisitWith,
+};
+
+use crate::ignore_side_imports::{IgnoreSideImportsConfig, SideImportKeeper};
+use crate::report::report;
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct DropUnusedImportsConfig {
+    /// off by default, imports unused by the chunk itself may still be wanted by other plugins
+    pub enabled: bool,
+}
+
+/// Imports left without specifiers are dropped,
+/// or kept as side imports when matched by keep-list of `ignoreSideImports`.
+pub fn drop_unused_imports(side_imports: &IgnoreSideImportsConfig) -> impl VisitMut + Pass {
+    visit_mut_pass(DropUnusedImports {
+        keeper: if side_imports.enabled {
+            Some(SideImportKeeper::new(side_imports))
+        } else {
+            None
+        },
+    })
+}
+
+struct DropUnusedImports {
+    // none when side imports are never ignored
+    keeper: Option<SideImportKeeper>,
+}
+
+impl DropUnusedImports {
+    fn should_keep(&mut self, src: &str, with_attributes: bool) -> bool {
+        match &mut self.keeper {
+            Some(keeper) => keeper.should_keep(src, with_attributes),
+            None => true,
+        }
+    }
+}
+
+impl VisitMut for DropUnusedImports {
+    noop_visit_mut_type!();
+
+    fn visit_mut_module(&mut self, module: &mut Module) {
+        let mut collector = RefCollector {
+            used: HashSet::new(),
+        };
+        module.visit_with(&mut collector);
+        let used = collector.used;
+
+        let kept: Vec<bool> = module
+            .body
+            .iter_mut()
+            .map(|item| {
+                let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item else {
+                    return true;
+                };
+
+                // side imports are left to `ignoreSideImports`
+                if import_decl.specifiers.is_empty() {
+                    return true;
+                }
+
+                let src = import_decl.src.value.to_string_lossy().to_string();
+
+                import_decl.specifiers.retain(|specifier| {
+                    let used = used.contains(&local_of(specifier).to_id());
+
+                    if !used {
+                        report(
+                            "dropUnusedImports",
+                            "unused specifier",
+                            specifier.span(),
+                            || format!("{}#{}", src, imported_of(specifier)),
+                        );
+                    }
+
+                    used
+                });
+
+                if !import_decl.specifiers.is_empty()
+                    || self.should_keep(&src, import_decl.with.is_some())
+                {
+                    return true;
+                }
+
+                report(
+                    "dropUnusedImports",
+                    "empty import",
+                    import_decl.span,
+                    || src,
+                );
+
+                false
+            })
+            .collect();
+
+        let mut kept = kept.into_iter();
+        module.body.retain(|_| kept.next().unwrap_or(true));
+    }
+}
+
+fn local_of(specifier: &ImportSpecifier) -> &Ident {
+    match specifier {
+        ImportSpecifier::Named(s) => &s.local,
+        ImportSpecifier::Default(s) => &s.local,
+        ImportSpecifier::Namespace(s) => &s.local,
+    }
+}
+
+// `default`, `*` or imported name
+fn imported_of(specifier: &ImportSpecifier) -> String {
+    match specifier {
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            imported: Some(ModuleExportName::Ident(ident)),
+            ..
+        }) => ident.sym.to_string(),
+        ImportSpecifier::Named(ImportNamedSpecifier {
+            imported: Some(ModuleExportName::Str(s)),
+            ..
+        }) => s.value.to_string_lossy().to_string(),
+        ImportSpecifier::Named(s) => s.local.sym.to_string(),
+        ImportSpecifier::Default(_) => "default".into(),
+        ImportSpecifier::Namespace(_) => "*".into(),
+    }
+}
+
+// all references, except bindings of imports
+struct RefCollector {
+    used: HashSet<Id>,
+}
+
+impl Visit for RefCollector {
+    noop_visit_type!();
+
+    fn visit_import_decl(&mut self, _: &ImportDecl) {}
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.used.insert(ident.to_id());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::drop_unused_imports;
+    use crate::ignore_side_imports::IgnoreSideImportsConfig;
+    use crate::pattern::Pattern;
+    use crate::testing::assert_transform;
+
+    #[test]
+    fn unused_specifiers_should_drop() {
+        assert_transform(
+            |_| drop_unused_imports(&Default::default()),
+            r#"
+            import a, { b, c as d } from "x";
+            import * as ns from "y";
+            import { e } from "z";
+            import "side";
+            export const v = a(d);
+            export { e };
+            "#,
+            r#"
+            import a, { c as d } from "x";
+            import { e } from "z";
+            import "side";
+            export const v = a(d);
+            export { e };
+            "#,
+        );
+    }
+
+    #[test]
+    fn empty_imports_matched_keep_should_keep_as_side_imports() {
+        assert_transform(
+            |_| {
+                drop_unused_imports(&IgnoreSideImportsConfig {
+                    keep: vec![Pattern::try_from("polyfill/*".to_string()).unwrap()],
+                    ..Default::default()
+                })
+            },
+            r#"
+            import { x } from "polyfill/array";
+            import { y } from "./theme.css";
+            import { z } from "lib";
+            import data from "./data.json" with { type: "json" };
+            "#,
+            r#"
+            import "polyfill/array";
+            import "./theme.css";
+            import "./data.json" with { type: "json" };
+            "#,
+        );
+    }
+
+    #[test]
+    fn empty_imports_should_keep_as_side_imports_when_side_imports_not_ignored() {
+        assert_transform(
+            |_| {
+                drop_unused_imports(&IgnoreSideImportsConfig {
+                    enabled: false,
+                    ..Default::default()
+                })
+            },
+            r#"
+            import { z } from "lib";
+            "#,
+            r#"
+            import "lib";
+            "#,
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use std::mem;
+
+use serde::Deserialize;
+use swc_core::common::comments::Comments;
+use swc_core::common::{Span, DUMMY_SP};
+use swc_core::ecma::ast::{
+    ArrowExpr, AssignOp, AssignTarget, AwaitExpr, BindingIdent, BlockStmt, BlockStmtOrExpr,
+    CallExpr, Callee, Decl, ExportDecl, Expr, Function, Id, MemberProp, Module, ModuleDecl,
+    ModuleItem, ParenExpr, Pass, Pat, ReturnStmt, SimpleAssignTarget, Stmt, VarDecl, VarDeclKind,
+    VarDeclarator,
+};
+use swc_core::ecma::visit::{
+    noop_visit_mut_type, noop_visit_type, visit_mut_pass, Visit, VisitMut, VisitMutWith, VisitWith,
+};
+
+use crate::annotate_pure_calls::imports::ImportBindings;
+use crate::annotate_pure_calls::{
+    contains_impure_call, impure_callees, references, AnnotatePureCallsConfig,
+};
+use crate::pattern::Pattern;
+use crate::report::report;
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct FoldStaticPropsConfig {
+    /// off by default, init like `document.getElementById("x")` is not known to be fresh,
+    /// so assigns to it could be dropped together with the unused binding
+    pub enabled: bool,
+}
+
+/// `pure_calls` provides impure callees, which should never be folded into pure IIFE
+pub fn fold_static_props<C>(
+    comments: C,
+    pure_calls: &AnnotatePureCallsConfig,
+) -> impl VisitMut + Pass
+where
+    C: Comments + Clone,
+{
+    visit_mut_pass(FoldStaticProps {
+        comments: Some(comments),
+        impure_callees: impure_callees(pure_calls),
+        imports: ImportBindings::default(),
+    })
+}
+
+struct FoldStaticProps<C: Comments>
+where
+    C: Comments + Clone,
+{
+    comments: Option<C>,
+    impure_callees: Vec<Pattern>,
+    imports: ImportBindings,
+}
+
+impl<C> FoldStaticProps<C>
+where
+    C: Comments + Clone,
+{
+    // folded IIFE could be dropped when unused, so should be checked like `annotate_pure_calls`,
+    // no impure calls evaluated and init not referencing the binding
+    fn is_foldable(&self, item: &ModuleItem, binding: &Id, assigns: &[Stmt]) -> bool {
+        if let Some(init) = declared_init(item) {
+            if references(init, std::slice::from_ref(binding)) {
+                return false;
+            }
+        }
+
+        !contains_impure_call(item, &self.impure_callees, &self.imports)
+            && !contains_impure_call(assigns, &self.impure_callees, &self.imports)
+    }
+
+    // `const X = init; X.a = 1;` => `const X = /*#__PURE__*/ (() => { const X = init; X.a = 1; return X })();`
+    fn fold(&self, item: ModuleItem, assigns: Vec<Stmt>) -> ModuleItem {
+        let (export_span, decl) = match item {
+            ModuleItem::Stmt(Stmt::Decl(decl)) => (None, decl),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                (Some(export_decl.span), export_decl.decl)
+            }
+            _ => unreachable!("only declarations could be folded"),
+        };
+
+        let (span, kind, name) = match &decl {
+            Decl::Var(var_decl) => {
+                let Pat::Ident(name) = &var_decl.decls[0].name else {
+                    unreachable!("only identifier bindings could be folded")
+                };
+                (var_decl.span, var_decl.kind, name.clone())
+            }
+            // class binding is mutable like `let`
+            Decl::Class(class_decl) => (
+                class_decl.class.span,
+                VarDeclKind::Let,
+                BindingIdent::from(class_decl.ident.clone()),
+            ),
+            _ => unreachable!("only variable or class declarations could be folded"),
+        };
+
+        let mut stmts = Vec::with_capacity(assigns.len() + 2);
+        stmts.push(Stmt::Decl(decl));
+        stmts.extend(assigns);
+        stmts.push(Stmt::Return(ReturnStmt {
+            span: DUMMY_SP,
+            arg: Some(Box::new(Expr::Ident(name.id.clone()))),
+        }));
+
+        // inner declaration keeps the start position, so place the annotation after the name
+        let pos = name.id.span.hi;
+        self.comments.add_pure_comment(pos);
+
+        report("foldStaticProps", "static props", name.id.span, || {
+            name.id.sym.to_string()
+        });
+
+        let iife = CallExpr {
+            span: Span::new(pos, pos),
+            callee: Callee::Expr(Box::new(Expr::Paren(ParenExpr {
+                span: DUMMY_SP,
+                expr: Box::new(Expr::Arrow(ArrowExpr {
+                    body: Box::new(BlockStmtOrExpr::BlockStmt(BlockStmt {
+                        stmts,
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                })),
+            }))),
+            ..Default::default()
+        };
+
+        let decl = Decl::Var(Box::new(VarDecl {
+            span,
+            kind,
+            decls: vec![VarDeclarator {
+                span: DUMMY_SP,
+                name: Pat::Ident(name),
+                init: Some(Box::new(Expr::Call(iife))),
+                definite: false,
+            }],
+            ..Default::default()
+        }));
+
+        match export_span {
+            Some(span) => ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { span, decl })),
+            None => ModuleItem::Stmt(Stmt::Decl(decl)),
+        }
+    }
+}
+
+impl<C> VisitMut for FoldStaticProps<C>
+where
+    C: Comments + Clone,
+{
+    noop_visit_mut_type!();
+
+    fn visit_mut_module(&mut self, module: &mut Module) {
+        self.imports = ImportBindings::collect(module);
+        module.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        let mut folded = Vec::with_capacity(items.len());
+        let mut rest = mem::take(items).into_iter().peekable();
+
+        while let Some(item) = rest.next() {
+            let Some(binding) = declared_binding(&item) else {
+                folded.push(item);
+                continue;
+            };
+
+            let mut assigns = vec![];
+
+            while let Some(ModuleItem::Stmt(stmt)) = rest.peek() {
+                if !is_static_prop_assign(stmt, &binding) {
+                    break;
+                }
+                if let Some(ModuleItem::Stmt(stmt)) = rest.next() {
+                    assigns.push(stmt);
+                }
+            }
+
+            // top level await could not be moved into function
+            if assigns.is_empty()
+                || contains_await(&item)
+                || contains_await(&assigns)
+                || !self.is_foldable(&item, &binding, &assigns)
+            {
+                folded.push(item);
+                folded.extend(assigns.into_iter().map(ModuleItem::Stmt));
+                continue;
+            }
+
+            folded.push(self.fold(item, assigns));
+        }
+
+        *items = folded;
+    }
+}
+
+// `const X = init`, `class X {}` or exported ones
+fn declared_binding(item: &ModuleItem) -> Option<Id> {
+    let decl = match item {
+        ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => &export_decl.decl,
+        _ => return None,
+    };
+
+    match decl {
+        Decl::Var(var_decl) if !var_decl.declare && var_decl.decls.len() == 1 => {
+            match &var_decl.decls[0] {
+                VarDeclarator {
+                    name: Pat::Ident(ident),
+                    init: Some(_),
+                    ..
+                } => Some(ident.to_id()),
+                _ => None,
+            }
+        }
+        Decl::Class(class_decl) if !class_decl.declare => Some(class_decl.ident.to_id()),
+        _ => None,
+    }
+}
+
+fn declared_init(item: &ModuleItem) -> Option<&Expr> {
+    let decl = match item {
+        ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => &export_decl.decl,
+        _ => return None,
+    };
+
+    match decl {
+        Decl::Var(var_decl) => var_decl.decls.first()?.init.as_deref(),
+        _ => None,
+    }
+}
+
+// `X.a = value` or `X["a"] = value`
+fn is_static_prop_assign(stmt: &Stmt, binding: &Id) -> bool {
+    let Stmt::Expr(expr_stmt) = stmt else {
+        return false;
+    };
+
+    let Expr::Assign(assign_expr) = &*expr_stmt.expr else {
+        return false;
+    };
+
+    if assign_expr.op != AssignOp::Assign {
+        return false;
+    }
+
+    let AssignTarget::Simple(SimpleAssignTarget::Member(member_expr)) = &assign_expr.left else {
+        return false;
+    };
+
+    matches!(&*member_expr.obj, Expr::Ident(obj) if obj.to_id() == *binding)
+        && matches!(
+            member_expr.prop,
+            MemberProp::Ident(_) | MemberProp::Computed(_)
+        )
+}
+
+fn contains_await<N: VisitWith<AwaitFinder>>(node: &N) -> bool {
+    let mut finder = AwaitFinder { found: false };
+    node.visit_with(&mut finder);
+    finder.found
+}
+
+struct AwaitFinder {
+    found: bool,
+}
+
+impl Visit for AwaitFinder {
+    noop_visit_type!();
+
+    // await of nested functions belongs to themselves
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_await_expr(&mut self, _: &AwaitExpr) {
+        self.found = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::fold_static_props;
+    use crate::testing::assert_transform;
+
+    #[test]
+    fn static_props_should_fold_into_pure_iife() {
+        assert_transform(
+            |comments| fold_static_props(comments, &Default::default()),
+            r#"
+            import { component } from "vue";
+            export const Button = component(() => null);
+            Button.displayName = "Button";
+            Button["install"] = (app) => app.component(Button.displayName, Button);
+            console.log(Button);
+            "#,
+            r#"
+            import { component } from "vue";
+            export const Button = /*#__PURE__*/ (() => {
+                const Button = component(() => null);
+                Button.displayName = "Button";
+                Button["install"] = (app) => app.component(Button.displayName, Button);
+                return Button;
+            })();
+            console.log(Button);
+            "#,
+        );
+    }
+
+    #[test]
+    fn class_static_props_should_fold_into_let() {
+        assert_transform(
+            |comments| fold_static_props(comments, &Default::default()),
+            r#"
+            class Store {}
+            Store.instances = new Map();
+            "#,
+            r#"
+            let Store = /*#__PURE__*/ (() => {
+                class Store {}
+                Store.instances = new Map();
+                return Store;
+            })();
+            "#,
+        );
+    }
+
+    #[test]
+    fn other_statements_should_not_fold() {
+        assert_transform(
+            |comments| fold_static_props(comments, &Default::default()),
+            r#"
+            const a = {}, b = {};
+            b.x = 1;
+            const c = {};
+            c.x += 1;
+            other.x = c;
+            c.y = 1;
+            const d = create();
+            d.data = await load();
+            const e = {};
+            f();
+            e.x = 1;
+            "#,
+            r#"
+            const a = {}, b = {};
+            b.x = 1;
+            const c = {};
+            c.x += 1;
+            other.x = c;
+            c.y = 1;
+            const d = create();
+            d.data = await load();
+            const e = {};
+            f();
+            e.x = 1;
+            "#,
+        );
+    }
+
+    #[test]
+    fn impure_or_self_referencing_init_should_not_fold() {
+        assert_transform(
+            |comments| fold_static_props(comments, &Default::default()),
+            r#"
+            const t = setInterval(tick, 1000);
+            t.label = "x";
+            const obs = new MutationObserver(() => obs.disconnect());
+            obs.tag = 1;
+            const s = create();
+            s.timer = window.setTimeout(tick);
+            "#,
+            r#"
+            const t = setInterval(tick, 1000);
+            t.label = "x";
+            const obs = new MutationObserver(() => obs.disconnect());
+            obs.tag = 1;
+            const s = create();
+            s.timer = window.setTimeout(tick);
+            "#,
+        );
+    }
+}
@@ -0,0 +1,513 @@
+use std::mem;
+
+use serde::Deserialize;
+use swc_core::common::comments::Comments;
+use swc_core::common::DUMMY_SP;
+use swc_core::ecma::ast::{
+    AssignOp, AssignTarget, BinaryOp, BlockStmt, BlockStmtOrExpr, Callee, Decl, Expr, ExprOrSpread,
+    Id, Ident, MemberProp, ModuleDecl, ModuleItem, ObjectLit, Pass, Pat, Prop, PropOrSpread,
+    ReturnStmt, SimpleAssignTarget, Stmt, UnaryOp, VarDecl, VarDeclKind, VarDeclarator,
+};
+use swc_core::ecma::visit::{
+    noop_visit_mut_type, noop_visit_type, visit_mut_pass, Visit, VisitMut, VisitWith,
+};
+
+use crate::report::report;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct FoldTsEnumsConfig {
+    pub enabled: bool,
+}
+
+impl Default for FoldTsEnumsConfig {
+    fn default() -> Self {
+        FoldTsEnumsConfig { enabled: true }
+    }
+}
+
+pub fn fold_ts_enums<C>(comments: C) -> impl VisitMut + Pass
+where
+    C: Comments + Clone,
+{
+    visit_mut_pass(FoldTsEnums {
+        comments: Some(comments),
+    })
+}
+
+struct FoldTsEnums<C: Comments>
+where
+    C: Comments + Clone,
+{
+    comments: Option<C>,
+}
+
+impl<C> VisitMut for FoldTsEnums<C>
+where
+    C: Comments + Clone,
+{
+    noop_visit_mut_type!();
+
+    // `var E; (function (E) { ... })(E || (E = {}));` => `var E = /*#__PURE__*/ (function (E) { ...; return E })({});`
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        let mut folded = Vec::with_capacity(items.len());
+        let mut rest = mem::take(items).into_iter().peekable();
+
+        while let Some(mut item) = rest.next() {
+            let Some(var_decl) = enum_var_decl(&mut item) else {
+                folded.push(item);
+                continue;
+            };
+
+            let binding = var_decl.decls[0].name.as_ident().unwrap().to_id();
+
+            let Some(ModuleItem::Stmt(Stmt::Expr(expr_stmt))) = rest.next_if(
+                |next| matches!(next, ModuleItem::Stmt(stmt) if is_enum_iife(stmt, &binding)),
+            ) else {
+                folded.push(item);
+                continue;
+            };
+
+            let Expr::Call(mut call_expr) = *expr_stmt.expr else {
+                unreachable!("checked by is_enum_iife")
+            };
+
+            call_expr.args = vec![ExprOrSpread {
+                spread: None,
+                expr: Box::new(Expr::Object(ObjectLit::default())),
+            }];
+
+            if let Callee::Expr(callee) = &mut call_expr.callee {
+                return_param(callee);
+            }
+
+            self.comments.add_pure_comment(call_expr.span.lo);
+
+            report("foldTsEnums", "enum or namespace", call_expr.span, || {
+                binding.0.to_string()
+            });
+
+            var_decl.decls[0].init = Some(Box::new(Expr::Call(call_expr)));
+
+            folded.push(item);
+        }
+
+        *items = folded;
+    }
+}
+
+// `var E;` or `export var E;`
+fn enum_var_decl(item: &mut ModuleItem) -> Option<&mut VarDecl> {
+    let decl = match item {
+        ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => &mut export_decl.decl,
+        _ => return None,
+    };
+
+    let Decl::Var(var_decl) = decl else {
+        return None;
+    };
+
+    if var_decl.declare || var_decl.kind == VarDeclKind::Const || var_decl.decls.len() != 1 {
+        return None;
+    }
+
+    match &var_decl.decls[0] {
+        VarDeclarator {
+            name: Pat::Ident(_),
+            init: None,
+            ..
+        } => Some(var_decl),
+        _ => None,
+    }
+}
+
+// `(function (E) { ... })(E || (E = {}))`, which body only defines members of `E` and not refers outer `E`
+fn is_enum_iife(stmt: &Stmt, binding: &Id) -> bool {
+    let Stmt::Expr(expr_stmt) = stmt else {
+        return false;
+    };
+
+    let Expr::Call(call_expr) = &*expr_stmt.expr else {
+        return false;
+    };
+
+    let [ExprOrSpread { spread: None, expr }] = call_expr.args.as_slice() else {
+        return false;
+    };
+
+    if !is_enum_arg(expr, binding) {
+        return false;
+    }
+
+    let Callee::Expr(callee) = &call_expr.callee else {
+        return false;
+    };
+
+    let (params, body) = match callee.unwrap_parens() {
+        Expr::Fn(fn_expr) if !fn_expr.function.is_async && !fn_expr.function.is_generator => {
+            match &fn_expr.function.body {
+                Some(body) => (
+                    fn_expr
+                        .function
+                        .params
+                        .iter()
+                        .map(|p| &p.pat)
+                        .collect::<Vec<_>>(),
+                    body,
+                ),
+                None => return false,
+            }
+        }
+        Expr::Arrow(arrow_expr) if !arrow_expr.is_async => match &*arrow_expr.body {
+            BlockStmtOrExpr::BlockStmt(body) => (arrow_expr.params.iter().collect(), body),
+            _ => return false,
+        },
+        _ => return false,
+    };
+
+    let [Pat::Ident(param)] = params.as_slice() else {
+        return false;
+    };
+
+    let param = param.to_id();
+
+    body.stmts.iter().all(|stmt| is_enum_stmt(stmt, &param)) && !refers(body, binding)
+}
+
+// statements emitted by tsc for enums and namespaces, which have no side effects out of the IIFE,
+// `E[E["A"] = 0] = "A";`, `NS.x = value;`, `function f() {}` or `const y = value;`
+fn is_enum_stmt(stmt: &Stmt, param: &Id) -> bool {
+    match stmt {
+        Stmt::Empty(_) | Stmt::Decl(Decl::Fn(_)) => true,
+        Stmt::Decl(Decl::Var(var_decl)) => var_decl.decls.iter().all(|decl| {
+            matches!(decl.name, Pat::Ident(_))
+                && decl
+                    .init
+                    .as_deref()
+                    .is_none_or(|init| is_side_effect_free(init, param))
+        }),
+        Stmt::Expr(expr_stmt) => is_member_assign(&expr_stmt.expr, param),
+        _ => false,
+    }
+}
+
+// `E.x = value`, `E["x"] = value` or `E[E["x"] = 0] = "x"`
+fn is_member_assign(expr: &Expr, param: &Id) -> bool {
+    let Expr::Assign(assign_expr) = expr.unwrap_parens() else {
+        return false;
+    };
+
+    let AssignTarget::Simple(SimpleAssignTarget::Member(member_expr)) = &assign_expr.left else {
+        return false;
+    };
+
+    assign_expr.op == AssignOp::Assign
+        && is_binding(&member_expr.obj, param)
+        && match &member_expr.prop {
+            MemberProp::Ident(_) => true,
+            MemberProp::Computed(computed) => {
+                is_member_assign(&computed.expr, param)
+                    || is_side_effect_free(&computed.expr, param)
+            }
+            MemberProp::PrivateName(_) => false,
+        }
+        && is_side_effect_free(&assign_expr.right, param)
+}
+
+// values of enum members or namespace exports, no calls, no getters except of `E` itself
+fn is_side_effect_free(expr: &Expr, param: &Id) -> bool {
+    match expr.unwrap_parens() {
+        Expr::Lit(_) | Expr::Ident(_) | Expr::Fn(_) | Expr::Arrow(_) => true,
+        Expr::Tpl(tpl) => tpl
+            .exprs
+            .iter()
+            .all(|expr| is_side_effect_free(expr, param)),
+        Expr::Unary(unary_expr) => {
+            unary_expr.op != UnaryOp::Delete && is_side_effect_free(&unary_expr.arg, param)
+        }
+        Expr::Bin(bin_expr) => {
+            is_side_effect_free(&bin_expr.left, param)
+                && is_side_effect_free(&bin_expr.right, param)
+        }
+        Expr::Cond(cond_expr) => {
+            is_side_effect_free(&cond_expr.test, param)
+                && is_side_effect_free(&cond_expr.cons, param)
+                && is_side_effect_free(&cond_expr.alt, param)
+        }
+        Expr::Member(member_expr) => {
+            is_binding(&member_expr.obj, param)
+                && match &member_expr.prop {
+                    MemberProp::Ident(_) => true,
+                    MemberProp::Computed(computed) => matches!(&*computed.expr, Expr::Lit(_)),
+                    MemberProp::PrivateName(_) => false,
+                }
+        }
+        Expr::Array(array_lit) => array_lit.elems.iter().all(|elem| match elem {
+            Some(ExprOrSpread { spread: None, expr }) => is_side_effect_free(expr, param),
+            Some(_) => false,
+            None => true,
+        }),
+        Expr::Object(object_lit) => object_lit.props.iter().all(|prop| match prop {
+            PropOrSpread::Prop(prop) => match &**prop {
+                Prop::Shorthand(_) | Prop::Method(_) => true,
+                Prop::KeyValue(key_value) => {
+                    !key_value.key.is_computed() && is_side_effect_free(&key_value.value, param)
+                }
+                _ => false,
+            },
+            PropOrSpread::Spread(_) => false,
+        }),
+        _ => false,
+    }
+}
+
+// `E || (E = {})` emitted by tsc, or `E = {}`,
+// `E || {}` never assigns `E`, which could not be folded into `var E = ...`
+fn is_enum_arg(expr: &Expr, binding: &Id) -> bool {
+    match expr.unwrap_parens() {
+        Expr::Bin(bin_expr)
+            if bin_expr.op == BinaryOp::LogicalOr && is_binding(&bin_expr.left, binding) =>
+        {
+            is_empty_object_assign(&bin_expr.right, binding)
+        }
+        expr => is_empty_object_assign(expr, binding),
+    }
+}
+
+// `E = {}`
+fn is_empty_object_assign(expr: &Expr, binding: &Id) -> bool {
+    let Expr::Assign(assign_expr) = expr.unwrap_parens() else {
+        return false;
+    };
+
+    assign_expr.op == AssignOp::Assign
+        && matches!(
+            &assign_expr.left,
+            AssignTarget::Simple(SimpleAssignTarget::Ident(ident)) if ident.to_id() == *binding
+        )
+        && is_empty_object(&assign_expr.right)
+}
+
+fn is_binding(expr: &Expr, binding: &Id) -> bool {
+    matches!(expr.unwrap_parens(), Expr::Ident(ident) if ident.to_id() == *binding)
+}
+
+fn is_empty_object(expr: &Expr) -> bool {
+    matches!(expr.unwrap_parens(), Expr::Object(object_lit) if object_lit.props.is_empty())
+}
+
+fn refers(body: &BlockStmt, binding: &Id) -> bool {
+    let mut finder = RefFinder {
+        binding,
+        found: false,
+    };
+    body.visit_with(&mut finder);
+    finder.found
+}
+
+// append `return E` to body of IIFE
+fn return_param(callee: &mut Expr) {
+    let (param, body) = match callee {
+        Expr::Paren(paren_expr) => return return_param(&mut paren_expr.expr),
+        Expr::Fn(fn_expr) => (
+            fn_expr.function.params.first().map(|p| &p.pat),
+            fn_expr.function.body.as_mut(),
+        ),
+        Expr::Arrow(arrow_expr) => (
+            arrow_expr.params.first(),
+            match &mut *arrow_expr.body {
+                BlockStmtOrExpr::BlockStmt(body) => Some(body),
+                _ => None,
+            },
+        ),
+        _ => return,
+    };
+
+    if let (Some(Pat::Ident(param)), Some(body)) = (param, body) {
+        body.stmts.push(Stmt::Return(ReturnStmt {
+            span: DUMMY_SP,
+            arg: Some(Box::new(Expr::Ident(param.id.clone()))),
+        }));
+    }
+}
+
+struct RefFinder<'a> {
+    binding: &'a Id,
+    found: bool,
+}
+
+impl Visit for RefFinder<'_> {
+    noop_visit_type!();
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if ident.to_id() == *self.binding {
+            self.found = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::fold_ts_enums;
+    use crate::testing::assert_transform;
+
+    #[test]
+    fn enum_iife_should_fold_into_pure_call() {
+        assert_transform(
+            fold_ts_enums,
+            r#"
+            export var Color;
+            (function (Color) {
+                Color[Color["Red"] = 0] = "Red";
+                Color[Color["Green"] = 1] = "Green";
+            })(Color || (Color = {}));
+            var Kind;
+            ((Kind2) => {
+                Kind2["A"] = "a";
+            })(Kind || (Kind = {}));
+            "#,
+            r#"
+            export var Color = /*#__PURE__*/ (function (Color) {
+                Color[Color["Red"] = 0] = "Red";
+                Color[Color["Green"] = 1] = "Green";
+                return Color;
+            })({});
+            var Kind = /*#__PURE__*/ ((Kind2) => {
+                Kind2["A"] = "a";
+                return Kind2;
+            })({});
+            "#,
+        );
+    }
+
+    #[test]
+    fn namespace_iife_should_fold_into_pure_call() {
+        assert_transform(
+            fold_ts_enums,
+            r#"
+            var NS;
+            (function (NS) {
+                NS.version = "1";
+                function get() { return NS.version }
+                NS.get = get;
+            })(NS || (NS = {}));
+            "#,
+            r#"
+            var NS = /*#__PURE__*/ (function (NS) {
+                NS.version = "1";
+                function get() { return NS.version }
+                NS.get = get;
+                return NS;
+            })({});
+            "#,
+        );
+    }
+
+    #[test]
+    fn other_iife_should_not_fold() {
+        assert_transform(
+            fold_ts_enums,
+            r#"
+            var A;
+            (function (A2) { A.x = 1; })(A || (A = {}));
+            var B;
+            (function (B) { return B; })(B || (B = {}));
+            var C;
+            (function (C) {})(C || (C = { x: 1 }));
+            var D;
+            (function (D, x) {})(D || (D = {}), 1);
+            var E;
+            init();
+            (function (E) {})(E || (E = {}));
+            "#,
+            r#"
+            var A;
+            (function (A2) { A.x = 1; })(A || (A = {}));
+            var B;
+            (function (B) { return B; })(B || (B = {}));
+            var C;
+            (function (C) {})(C || (C = { x: 1 }));
+            var D;
+            (function (D, x) {})(D || (D = {}), 1);
+            var E;
+            init();
+            (function (E) {})(E || (E = {}));
+            "#,
+        );
+    }
+
+    #[test]
+    fn iife_not_assigning_binding_should_not_fold() {
+        assert_transform(
+            fold_ts_enums,
+            r#"
+            var Color;
+            (function (Color) {
+                Color[Color["Red"] = 0] = "Red";
+            })(Color || {});
+            var Kind;
+            (function (Kind) {
+                Kind["A"] = "a";
+            })(Kind = {});
+            "#,
+            r#"
+            var Color;
+            (function (Color) {
+                Color[Color["Red"] = 0] = "Red";
+            })(Color || {});
+            var Kind = /*#__PURE__*/ (function (Kind) {
+                Kind["A"] = "a";
+                return Kind;
+            })({});
+            "#,
+        );
+    }
+
+    #[test]
+    fn iife_with_side_effects_should_not_fold() {
+        assert_transform(
+            fold_ts_enums,
+            r#"
+            export var NS;
+            (function (NS) {
+                NS.timer = setInterval(tick, 1000);
+                register(NS);
+            })(NS || (NS = {}));
+            var Color;
+            (function (Color) {
+                Color[Color["Red"] = init()] = "Red";
+            })(Color || (Color = {}));
+            var Config;
+            (function (Config) {
+                const base = load();
+                Config.base = base;
+            })(Config || (Config = {}));
+            var Other;
+            (function (Other) {
+                window.other = Other;
+            })(Other || (Other = {}));
+            "#,
+            r#"
+            export var NS;
+            (function (NS) {
+                NS.timer = setInterval(tick, 1000);
+                register(NS);
+            })(NS || (NS = {}));
+            var Color;
+            (function (Color) {
+                Color[Color["Red"] = init()] = "Red";
+            })(Color || (Color = {}));
+            var Config;
+            (function (Config) {
+                const base = load();
+                Config.base = base;
+            })(Config || (Config = {}));
+            var Other;
+            (function (Other) {
+                window.other = Other;
+            })(Other || (Other = {}));
+            "#,
+        );
+    }
+}
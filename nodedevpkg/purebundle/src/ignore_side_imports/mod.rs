@@ -1,26 +1,244 @@
-use swc_core::ecma::ast::{ModuleDecl, ModuleItem, Pass};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use swc_core::common::Span;
+use swc_core::ecma::ast::{
+    Callee, Expr, ExprOrSpread, Id, Lit, Module, ModuleDecl, ModuleItem, Pass, Script, Stmt,
+};
+use swc_core::ecma::utils::collect_decls;
 use swc_core::ecma::visit::{noop_visit_mut_type, visit_mut_pass, VisitMut, VisitMutWith};
 
-pub fn ignore_side_imports() -> impl VisitMut + Pass {
-    visit_mut_pass(IgnoreSideImports {})
+use crate::pattern::{matches_any, Pattern};
+use crate::report::report;
+
+pub use self::side_effects::SideEffects;
+use self::side_effects::SideEffectsResolver;
+
+mod side_effects;
+
+// style sheets and inlined assets, which only work by side effects
+const ASSET_PATTERNS: [&str; 2] = [
+    r"/\.(css|scss|sass|less|styl|stylus)(\?.*)?$/",
+    r"/\.svg\?inline$/",
+];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct IgnoreSideImportsConfig {
+    pub enabled: bool,
+    /// side imports matched will be kept, like `core-js/*` or `/^reflect-metadata$/`
+    pub keep: Vec<Pattern>,
+    /// keep side imports of style sheets and inlined assets
+    pub keep_assets: bool,
+    /// keep side imports with import attributes, like `import "./theme.css" with { type: "css" }`
+    pub keep_with_attributes: bool,
+    /// `sideEffects` of packages, when set, only side imports of packages declared side effects free will be dropped
+    pub side_effects: Option<HashMap<String, SideEffects>>,
+    /// `node_modules` directory to read `sideEffects` from package.json of packages not in `sideEffects`
+    pub node_modules: Option<String>,
+}
+
+impl Default for IgnoreSideImportsConfig {
+    fn default() -> Self {
+        IgnoreSideImportsConfig {
+            enabled: true,
+            keep: vec![],
+            keep_assets: true,
+            keep_with_attributes: true,
+            side_effects: None,
+            node_modules: None,
+        }
+    }
+}
+
+pub fn ignore_side_imports(config: &IgnoreSideImportsConfig) -> impl VisitMut + Pass {
+    visit_mut_pass(IgnoreSideImports {
+        keeper: SideImportKeeper::new(config),
+        decls: HashSet::new(),
+    })
+}
+
+/// Decides whether side import should be kept by keep-list, assets and `sideEffects`.
+pub(crate) struct SideImportKeeper {
+    keep: Vec<Pattern>,
+    keep_with_attributes: bool,
+    side_effects: Option<SideEffectsResolver>,
+}
+
+impl SideImportKeeper {
+    pub fn new(config: &IgnoreSideImportsConfig) -> SideImportKeeper {
+        let mut keep = config.keep.clone();
+
+        if config.keep_assets {
+            keep.extend(
+                ASSET_PATTERNS
+                    .iter()
+                    .map(|p| Pattern::try_from(p.to_string()).unwrap()),
+            );
+        }
+
+        let side_effects = if config.side_effects.is_some() || config.node_modules.is_some() {
+            Some(SideEffectsResolver::new(
+                config.side_effects.clone().unwrap_or_default(),
+                config.node_modules.as_ref().map(PathBuf::from),
+            ))
+        } else {
+            None
+        };
+
+        SideImportKeeper {
+            keep,
+            keep_with_attributes: config.keep_with_attributes,
+            side_effects,
+        }
+    }
+
+    pub fn should_keep(&mut self, src: &str, with_attributes: bool) -> bool {
+        if (with_attributes && self.keep_with_attributes) || matches_any(&self.keep, src) {
+            return true;
+        }
+
+        match &mut self.side_effects {
+            Some(side_effects) => side_effects.has_side_effects(src),
+            None => false,
+        }
+    }
+}
+
+struct IgnoreSideImports {
+    keeper: SideImportKeeper,
+    // bindings of module or script, to tell global `require` from local one
+    decls: HashSet<Id>,
+}
+
+// `import "x"`, `export {} from "x"`, `import("x");` or `require("x");`
+struct SideImport {
+    src: String,
+    span: Span,
+    with_attributes: bool,
+    // form of side import, as reason of report
+    form: &'static str,
 }
 
-struct IgnoreSideImports {}
+impl IgnoreSideImports {
+    fn side_import(&self, item: &ModuleItem) -> Option<SideImport> {
+        match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl))
+                if import_decl.specifiers.is_empty() =>
+            {
+                Some(SideImport {
+                    src: import_decl.src.value.to_string_lossy().to_string(),
+                    span: import_decl.span,
+                    with_attributes: import_decl.with.is_some(),
+                    form: "side import",
+                })
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(named_export))
+                if named_export.specifiers.is_empty() =>
+            {
+                named_export.src.as_ref().map(|src| SideImport {
+                    src: src.value.to_string_lossy().to_string(),
+                    span: named_export.span,
+                    with_attributes: named_export.with.is_some(),
+                    form: "empty re-export",
+                })
+            }
+            ModuleItem::Stmt(stmt) => self.side_call(stmt),
+            _ => None,
+        }
+    }
+
+    // `import("x", { with })` is never dropped
+    fn side_call(&self, stmt: &Stmt) -> Option<SideImport> {
+        let Stmt::Expr(expr_stmt) = stmt else {
+            return None;
+        };
+
+        let Expr::Call(call_expr) = expr_stmt.expr.unwrap_parens() else {
+            return None;
+        };
+
+        let form = match &call_expr.callee {
+            Callee::Import(_) => "dynamic import",
+            Callee::Expr(callee) => match callee.unwrap_parens() {
+                Expr::Ident(ident)
+                    if &*ident.sym == "require" && !self.decls.contains(&ident.to_id()) =>
+                {
+                    "require"
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        match call_expr.args.as_slice() {
+            [ExprOrSpread { spread: None, expr }] => match &**expr {
+                Expr::Lit(Lit::Str(src)) => Some(SideImport {
+                    src: src.value.to_string_lossy().to_string(),
+                    span: expr_stmt.span,
+                    with_attributes: false,
+                    form,
+                }),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn kept<T>(
+        &mut self,
+        items: &[T],
+        side_import: fn(&Self, &T) -> Option<SideImport>,
+    ) -> Vec<bool> {
+        items
+            .iter()
+            .map(|item| {
+                let Some(side_import) = side_import(self, item) else {
+                    return true;
+                };
+
+                if self
+                    .keeper
+                    .should_keep(&side_import.src, side_import.with_attributes)
+                {
+                    return true;
+                }
+
+                report(
+                    "ignoreSideImports",
+                    side_import.form,
+                    side_import.span,
+                    || side_import.src,
+                );
+
+                false
+            })
+            .collect()
+    }
+}
 
 impl VisitMut for IgnoreSideImports {
     noop_visit_mut_type!();
 
+    fn visit_mut_module(&mut self, module: &mut Module) {
+        self.decls = collect_decls::<Id, _>(&*module).into_iter().collect();
+        module.visit_mut_children_with(self);
+    }
+
+    // CJS chunks
+    fn visit_mut_script(&mut self, script: &mut Script) {
+        self.decls = collect_decls::<Id, _>(&*script).into_iter().collect();
+
+        let mut kept = self.kept(&script.body, Self::side_call).into_iter();
+        script.body.retain(|_| kept.next().unwrap_or(true));
+    }
+
     fn visit_mut_module_items(&mut self, expr: &mut Vec<ModuleItem>) {
         expr.visit_mut_children_with(self);
 
-        expr.retain(|node| {
-            if let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = node {
-                if import_decl.specifiers.is_empty() {
-                    return false;
-                }
-            }
-            return true;
-        });
+        let mut kept = self.kept(expr, Self::side_import).into_iter();
+        expr.retain(|_| kept.next().unwrap_or(true));
     }
 }
 
@@ -28,12 +246,12 @@ impl VisitMut for IgnoreSideImports {
 mod test {
     use swc_core::common::Mark;
     use swc_core::ecma::ast::Pass;
+    use swc_core::ecma::parser::{Syntax, TsSyntax};
     use swc_core::ecma::transforms::base::resolver;
     use swc_core::ecma::transforms::testing::{test, Tester};
-    use swc_core::ecma::{
-        parser::{Syntax, TsSyntax},
-        visit::visit_mut_pass,
-    };
+
+    use super::IgnoreSideImportsConfig;
+    use crate::pattern::Pattern;
 
     const SYNTAX: Syntax = Syntax::Typescript(TsSyntax {
         tsx: true,
@@ -44,9 +262,13 @@ mod test {
     });
 
     fn runner(_: &mut Tester) -> impl Pass {
+        runner_with(IgnoreSideImportsConfig::default())
+    }
+
+    fn runner_with(config: IgnoreSideImportsConfig) -> impl Pass {
         (
             resolver(Mark::new(), Mark::new(), false),
-            visit_mut_pass(super::IgnoreSideImports {}),
+            super::ignore_side_imports(&config),
         )
     }
 
@@ -62,4 +284,151 @@ mod test {
              import { x } from "x"
         "#
     );
+
+    swc_core::ecma::transforms::testing::test_inline!(
+        SYNTAX,
+        runner,
+        /* Name */ empty_re_export_dynamic_import_and_require_should_drop,
+        /* Input */
+        r#"
+            export {} from "x"
+            export { y } from "y"
+            import("x");
+            require("x");
+            require("./theme.css");
+            import("x").then(init);
+            const m = require("x");
+        "#,
+        /* Output */
+        r#"
+            export { y } from "y"
+            require("./theme.css");
+            import("x").then(init);
+            const m = require("x");
+        "#
+    );
+
+    swc_core::ecma::transforms::testing::test_inline!(
+        SYNTAX,
+        runner,
+        /* Name */ require_of_local_binding_should_keep,
+        /* Input */
+        r#"
+            import { createRequire } from "module";
+            const require = createRequire(import.meta.url);
+            require("x");
+        "#,
+        /* Output */
+        r#"
+            import { createRequire } from "module";
+            const require = createRequire(import.meta.url);
+            require("x");
+        "#
+    );
+
+    swc_core::ecma::transforms::testing::test_inline!(
+        SYNTAX,
+        runner,
+        /* Name */ side_import_with_attributes_should_keep,
+        /* Input */
+        r#"
+            import "./sheet.js" with { type: "css" }
+            import "./data.json" with { type: "json" }
+            export {} from "./data.json" with { type: "json" }
+            import "./data.json"
+        "#,
+        /* Output */
+        r#"
+            import "./sheet.js" with { type: "css" }
+            import "./data.json" with { type: "json" }
+            export {} from "./data.json" with { type: "json" }
+        "#
+    );
+
+    swc_core::ecma::transforms::testing::test_inline!(
+        SYNTAX,
+        |_| runner_with(IgnoreSideImportsConfig {
+            keep_with_attributes: false,
+            ..Default::default()
+        }),
+        /* Name */ side_import_with_attributes_should_drop_when_not_kept,
+        /* Input */
+        r#"
+            import "./data.json" with { type: "json" }
+        "#,
+        /* Output */
+        r#"
+        "#
+    );
+
+    swc_core::ecma::transforms::testing::test_inline!(
+        SYNTAX,
+        runner,
+        /* Name */ side_import_of_assets_should_keep,
+        /* Input */
+        r#"
+            import "./theme.css"
+            import "./theme.scss"
+            import "./button.css?inline"
+            import "./icon.svg?inline"
+            import "./icon.svg"
+        "#,
+        /* Output */
+        r#"
+            import "./theme.css"
+            import "./theme.scss"
+            import "./button.css?inline"
+            import "./icon.svg?inline"
+        "#
+    );
+
+    swc_core::ecma::transforms::testing::test_inline!(
+        SYNTAX,
+        |_| runner_with(IgnoreSideImportsConfig {
+            keep: vec![
+                Pattern::try_from("core-js/*".to_string()).unwrap(),
+                Pattern::try_from("/^reflect-metadata$/".to_string()).unwrap(),
+            ],
+            keep_assets: false,
+            ..Default::default()
+        }),
+        /* Name */ side_import_matched_keep_should_keep,
+        /* Input */
+        r#"
+            import "core-js/stable/array"
+            import "reflect-metadata"
+            import "reflect-metadata/lite"
+            import "./theme.css"
+        "#,
+        /* Output */
+        r#"
+            import "core-js/stable/array"
+            import "reflect-metadata"
+        "#
+    );
+
+    swc_core::ecma::transforms::testing::test_inline!(
+        SYNTAX,
+        |_| runner_with(IgnoreSideImportsConfig {
+            side_effects: Some(
+                serde_json::from_str(r#"{ "@innoai-tech/lodash": false, "polyfill": true }"#)
+                    .unwrap()
+            ),
+            ..Default::default()
+        }),
+        /* Name */ side_import_should_drop_only_when_declared_side_effects_free,
+        /* Input */
+        r#"
+            import "@innoai-tech/lodash"
+            import "polyfill"
+            import "unknown"
+            import "./chunk.mjs"
+        "#,
+        /* Output */
+        r#"
+            import "polyfill"
+            import "unknown"
+            import "./chunk.mjs"
+        "#
+    );
 }
@@ -0,0 +1,278 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::pattern::{matches_any, Pattern};
+
+/// `sideEffects` field of package.json
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SideEffects {
+    Flag(bool),
+    Files(Vec<Pattern>),
+}
+
+impl SideEffects {
+    // `files` are paths in package resolved from the import, none when could not be resolved.
+    fn has_side_effects(&self, files: Option<&[String]>) -> bool {
+        match self {
+            SideEffects::Flag(flag) => *flag,
+            // treat as with side effects when not knowing which file is imported
+            SideEffects::Files(_) if files.is_none() => true,
+            SideEffects::Files(patterns) => files.unwrap_or_default().iter().any(|file| {
+                matches_any(patterns, file) || matches_any(patterns, &format!("./{}", file))
+            }),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Package {
+    side_effects: Option<SideEffects>,
+    exports: Option<Value>,
+}
+
+pub struct SideEffectsResolver {
+    side_effects: HashMap<String, SideEffects>,
+    packages: HashMap<String, Package>,
+    node_modules: Option<PathBuf>,
+}
+
+impl SideEffectsResolver {
+    pub fn new(
+        side_effects: HashMap<String, SideEffects>,
+        node_modules: Option<PathBuf>,
+    ) -> SideEffectsResolver {
+        SideEffectsResolver {
+            side_effects,
+            packages: HashMap::new(),
+            node_modules,
+        }
+    }
+
+    /// Only packages declared side effects free could be dropped,
+    /// relative imports and packages without declaration are treated as with side effects.
+    pub fn has_side_effects(&mut self, src: &str) -> bool {
+        let Some((name, subpath)) = split_package_name(src) else {
+            return true;
+        };
+
+        if !self.packages.contains_key(name) {
+            let package = self.read_package(name).unwrap_or_default();
+            self.packages.insert(name.to_string(), package);
+        }
+
+        let package = &self.packages[name];
+
+        let Some(side_effects) = self
+            .side_effects
+            .get(name)
+            .or(package.side_effects.as_ref())
+        else {
+            return true;
+        };
+
+        side_effects.has_side_effects(resolve_subpath(subpath, package.exports.as_ref()).as_deref())
+    }
+
+    fn read_package(&self, name: &str) -> Option<Package> {
+        let package_json = self.node_modules.as_ref()?.join(name).join("package.json");
+
+        let mut pkg: HashMap<String, Value> =
+            serde_json::from_str(&fs::read_to_string(package_json).ok()?).ok()?;
+
+        Some(Package {
+            side_effects: pkg
+                .remove("sideEffects")
+                .and_then(|side_effects| serde_json::from_value(side_effects).ok()),
+            exports: pkg.remove("exports"),
+        })
+    }
+}
+
+// files of `subpath` by `exports` of package.json, all targets of conditions included,
+// without `exports` only subpath with extension like `dist/index.css` is taken as file
+fn resolve_subpath(subpath: &str, exports: Option<&Value>) -> Option<Vec<String>> {
+    let Some(exports) = exports else {
+        return Path::new(subpath)
+            .extension()
+            .is_some()
+            .then(|| vec![subpath.to_string()]);
+    };
+
+    let key = if subpath.is_empty() {
+        ".".to_string()
+    } else {
+        format!("./{}", subpath)
+    };
+
+    let target = match exports {
+        // `{ "./sub": ..., "./*": ... }`
+        Value::Object(map) if map.keys().all(|key| key.starts_with('.')) => match map.get(&key) {
+            Some(target) => target.clone(),
+            None => map.iter().find_map(|(pattern, target)| {
+                let (prefix, suffix) = pattern.split_once('*')?;
+                let matched = key.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                Some(replace_star(target, matched))
+            })?,
+        },
+        // `"./index.js"` or `{ "import": ..., "require": ... }` of entry
+        target if subpath.is_empty() => target.clone(),
+        _ => return None,
+    };
+
+    let mut files = vec![];
+    collect_targets(&target, &mut files);
+
+    (!files.is_empty()).then_some(files)
+}
+
+fn replace_star(target: &Value, matched: &str) -> Value {
+    match target {
+        Value::String(s) => Value::String(s.replace('*', matched)),
+        Value::Array(targets) => Value::Array(
+            targets
+                .iter()
+                .map(|target| replace_star(target, matched))
+                .collect(),
+        ),
+        Value::Object(conditions) => Value::Object(
+            conditions
+                .iter()
+                .map(|(condition, target)| (condition.clone(), replace_star(target, matched)))
+                .collect(),
+        ),
+        target => target.clone(),
+    }
+}
+
+fn collect_targets(target: &Value, files: &mut Vec<String>) {
+    match target {
+        Value::String(file) => files.push(file.trim_start_matches("./").to_string()),
+        Value::Array(targets) => targets
+            .iter()
+            .for_each(|target| collect_targets(target, files)),
+        Value::Object(conditions) => conditions
+            .values()
+            .for_each(|target| collect_targets(target, files)),
+        _ => {}
+    }
+}
+
+// `@scope/name/sub/path` => (`@scope/name`, `sub/path`)
+fn split_package_name(src: &str) -> Option<(&str, &str)> {
+    if src.starts_with('.') || src.starts_with('/') || src.contains(':') {
+        return None;
+    }
+
+    let mut end = src.find('/').unwrap_or(src.len());
+
+    if src.starts_with('@') {
+        end = match src[end..].get(1..).and_then(|rest| rest.find('/')) {
+            Some(i) => end + 1 + i,
+            None => src.len(),
+        };
+    }
+
+    Some((&src[..end], src[end..].trim_start_matches('/')))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{resolve_subpath, split_package_name, SideEffects, SideEffectsResolver};
+
+    #[test]
+    fn package_name_should_split() {
+        assert_eq!(split_package_name("lodash"), Some(("lodash", "")));
+        assert_eq!(
+            split_package_name("core-js/stable/array"),
+            Some(("core-js", "stable/array"))
+        );
+        assert_eq!(split_package_name("@scope/pkg"), Some(("@scope/pkg", "")));
+        assert_eq!(
+            split_package_name("@scope/pkg/dist/index.css"),
+            Some(("@scope/pkg", "dist/index.css"))
+        );
+        assert_eq!(split_package_name("./chunk.mjs"), None);
+        assert_eq!(split_package_name("node:fs"), None);
+    }
+
+    #[test]
+    fn side_effects_should_follow_declaration() {
+        let side_effects: HashMap<String, SideEffects> = serde_json::from_str(
+            r#"{ "free": false, "effect": true, "files": ["*.css", "./dist/polyfill.js"] }"#,
+        )
+        .unwrap();
+
+        let mut resolver = SideEffectsResolver::new(side_effects, None);
+
+        assert!(!resolver.has_side_effects("free"));
+        assert!(!resolver.has_side_effects("free/sub"));
+        assert!(resolver.has_side_effects("effect"));
+        assert!(resolver.has_side_effects("files"));
+        assert!(resolver.has_side_effects("files/dist/index.css"));
+        assert!(resolver.has_side_effects("files/dist/polyfill.js"));
+        assert!(!resolver.has_side_effects("files/dist/util.js"));
+        // could be mapped to any file by `exports`
+        assert!(resolver.has_side_effects("files/polyfill"));
+        assert!(resolver.has_side_effects("unknown"));
+        assert!(resolver.has_side_effects("./chunk.mjs"));
+    }
+
+    #[test]
+    fn subpath_should_resolve_by_exports() {
+        let exports = serde_json::json!({
+            ".": { "import": "./dist/index.mjs", "require": "./dist/index.cjs" },
+            "./polyfill": "./dist/polyfill.js",
+            "./icons/*": "./dist/icons/*.js",
+            "./package.json": "./package.json"
+        });
+
+        assert_eq!(
+            resolve_subpath("", Some(&exports)),
+            Some(vec!["dist/index.mjs".into(), "dist/index.cjs".into()])
+        );
+        assert_eq!(
+            resolve_subpath("polyfill", Some(&exports)),
+            Some(vec!["dist/polyfill.js".into()])
+        );
+        assert_eq!(
+            resolve_subpath("icons/add", Some(&exports)),
+            Some(vec!["dist/icons/add.js".into()])
+        );
+        assert_eq!(resolve_subpath("dist/util.js", Some(&exports)), None);
+        assert_eq!(
+            resolve_subpath("", Some(&serde_json::json!("./index.js"))),
+            Some(vec!["index.js".into()])
+        );
+        assert_eq!(resolve_subpath("", None), None);
+        assert_eq!(resolve_subpath("polyfill", None), None);
+    }
+
+    #[test]
+    fn side_effects_should_match_files_resolved_by_exports() {
+        let node_modules = std::env::temp_dir().join("purebundle-side-effects-exports");
+        let pkg = node_modules.join("pkg");
+        std::fs::create_dir_all(&pkg).unwrap();
+        std::fs::write(
+            pkg.join("package.json"),
+            r#"{ "exports": { ".": "./dist/index.js", "./polyfill": "./dist/polyfill.js", "./util": "./dist/util.js" } }"#,
+        )
+        .unwrap();
+
+        let side_effects: HashMap<String, SideEffects> =
+            serde_json::from_str(r#"{ "pkg": ["./dist/polyfill.js"] }"#).unwrap();
+
+        let mut resolver = SideEffectsResolver::new(side_effects, Some(node_modules));
+
+        assert!(resolver.has_side_effects("pkg/polyfill"));
+        assert!(!resolver.has_side_effects("pkg/util"));
+        assert!(!resolver.has_side_effects("pkg"));
+        assert!(resolver.has_side_effects("pkg/dist/util.js"));
+    }
+}
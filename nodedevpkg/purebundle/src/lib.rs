@@ -1,15 +1,110 @@
+use swc_core::common::comments::Comments;
+use swc_core::common::Spanned;
+use swc_core::plugin::errors::HANDLER;
 use swc_core::plugin::metadata::TransformPluginProgramMetadata;
 use swc_core::{ecma::ast::Program, plugin::plugin_transform};
 
+pub use self::config::Config;
+pub use self::native::{transform_code, TransformOptions, TransformOutput};
+pub use self::report::ReportEntry;
+
+use self::annotate_no_side_effects::*;
 use self::annotate_pure_calls::*;
+use self::drop_unused_imports::*;
+use self::fold_static_props::*;
+use self::fold_ts_enums::*;
 use self::ignore_side_imports::*;
+use self::merge_imports::*;
+use self::report::*;
+use self::strip_dev_code::*;
 
+mod annotate_no_side_effects;
 mod annotate_pure_calls;
+mod config;
+mod drop_unused_imports;
+mod fold_static_props;
+mod fold_ts_enums;
 mod ignore_side_imports;
+mod merge_imports;
+mod native;
+mod pattern;
+mod report;
+mod strip_dev_code;
+#[cfg(test)]
+mod testing;
 
 #[plugin_transform]
 pub fn process_transform(program: Program, metadata: TransformPluginProgramMetadata) -> Program {
+    let config =
+        match Config::from_json(&metadata.get_transform_plugin_config().unwrap_or_default()) {
+            Ok(config) => config,
+            Err(err) => {
+                HANDLER.with(|handler| handler.err(&err));
+                return program;
+            }
+        };
+
+    let (program, entries) = collect(config.report, || {
+        transform(program, metadata.comments, &config)
+    });
+
+    if config.report && !program.span().lo.is_dummy() {
+        let mut entries = entries;
+        locate(&mut entries, &metadata.source_map);
+        add_report_comment(&metadata.comments, program.span().lo, &entries);
+    }
+
+    program
+}
+
+pub(crate) fn transform<C>(program: Program, comments: C, config: &Config) -> Program
+where
+    C: Comments + Clone,
+{
+    let mut program = program;
+
+    // dead branches go away before any analysis and annotation
+    if config.strip_dev_code.enabled {
+        program = program.apply(&mut strip_dev_code(&config.strip_dev_code));
+    }
+
+    if config.ignore_side_imports.enabled {
+        program = program.apply(&mut ignore_side_imports(&config.ignore_side_imports));
+    }
+
+    if config.fold_static_props.enabled {
+        program = program.apply(&mut fold_static_props(
+            comments.clone(),
+            &config.annotate_pure_calls,
+        ));
+    }
+
+    if config.fold_ts_enums.enabled {
+        program = program.apply(&mut fold_ts_enums(comments.clone()));
+    }
+
+    if config.annotate_pure_calls.enabled {
+        program = program.apply(&mut annotate_pure_calls(
+            comments.clone(),
+            &config.annotate_pure_calls,
+        ));
+    }
+
+    if config.annotate_no_side_effects.enabled {
+        program = program.apply(&mut annotate_no_side_effects(
+            comments.clone(),
+            &config.annotate_no_side_effects,
+            &config.annotate_pure_calls,
+        ));
+    }
+
+    if config.drop_unused_imports.enabled {
+        program = program.apply(&mut drop_unused_imports(&config.ignore_side_imports));
+    }
+
+    if config.merge_imports.enabled {
+        program = program.apply(&mut merge_imports());
+    }
+
     program
-        .apply(&mut ignore_side_imports())
-        .apply(&mut annotate_pure_calls(metadata.comments.clone()))
 }
@@ -0,0 +1,221 @@
+use std::mem;
+
+use serde::Deserialize;
+use swc_core::common::{EqIgnoreSpan, DUMMY_SP};
+use swc_core::ecma::ast::{
+    ImportDecl, ImportDefaultSpecifier, ImportNamedSpecifier, ImportPhase, ImportSpecifier, Module,
+    ModuleDecl, ModuleExportName, ModuleItem, Pass,
+};
+use swc_core::ecma::visit::{noop_visit_mut_type, visit_mut_pass, VisitMut};
+
+use crate::report::report;
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct MergeImportsConfig {
+    /// off by default, it rewrites import statements beyond annotations,
+    /// and is only needed when chunks are not deduplicated by the bundler
+    pub enabled: bool,
+}
+
+pub fn merge_imports() -> impl VisitMut + Pass {
+    visit_mut_pass(MergeImports)
+}
+
+struct MergeImports;
+
+enum Slot {
+    Item(ModuleItem),
+    Imports(usize),
+}
+
+impl VisitMut for MergeImports {
+    noop_visit_mut_type!();
+
+    fn visit_mut_module(&mut self, module: &mut Module) {
+        let mut groups: Vec<Vec<ImportDecl>> = vec![];
+        let mut slots = Vec::with_capacity(module.body.len());
+
+        for item in mem::take(&mut module.body) {
+            match item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl))
+                    if import_decl.phase == ImportPhase::Evaluation =>
+                {
+                    match groups
+                        .iter()
+                        .position(|group| is_same_source(&group[0], &import_decl))
+                    {
+                        Some(i) => {
+                            report("mergeImports", "duplicate import", import_decl.span, || {
+                                import_decl.src.value.to_string_lossy().to_string()
+                            });
+                            groups[i].push(import_decl)
+                        }
+                        None => {
+                            slots.push(Slot::Imports(groups.len()));
+                            groups.push(vec![import_decl]);
+                        }
+                    }
+                }
+                item => slots.push(Slot::Item(item)),
+            }
+        }
+
+        // merged imports take place of the first one, to keep evaluation order of modules
+        for slot in slots {
+            match slot {
+                Slot::Item(item) => module.body.push(item),
+                Slot::Imports(i) => module.body.extend(
+                    merge(mem::take(&mut groups[i]))
+                        .into_iter()
+                        .map(|import_decl| ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl))),
+                ),
+            }
+        }
+    }
+}
+
+// same module with same `import type` and import attributes
+fn is_same_source(a: &ImportDecl, b: &ImportDecl) -> bool {
+    a.src.value == b.src.value && a.type_only == b.type_only && a.with.eq_ignore_span(&b.with)
+}
+
+// `import a, { b }`, `import a, * as ns` or `import * as ns`, as less as possible
+fn merge(mut imports: Vec<ImportDecl>) -> Vec<ImportDecl> {
+    if imports.len() == 1 {
+        return imports;
+    }
+
+    let mut default = None;
+    let mut named = vec![];
+    let mut namespaces = vec![];
+
+    for import_decl in &mut imports {
+        for specifier in mem::take(&mut import_decl.specifiers) {
+            match specifier {
+                ImportSpecifier::Default(s) if default.is_none() => default = Some(s),
+                // `import b from "x"` => `import { default as b } from "x"`
+                ImportSpecifier::Default(ImportDefaultSpecifier { span, local }) => {
+                    named.push(ImportSpecifier::Named(ImportNamedSpecifier {
+                        span,
+                        local,
+                        imported: Some(ModuleExportName::Ident("default".into())),
+                        is_type_only: false,
+                    }))
+                }
+                ImportSpecifier::Named(_) => named.push(specifier),
+                ImportSpecifier::Namespace(_) => namespaces.push(specifier),
+            }
+        }
+    }
+
+    let first = imports.swap_remove(0);
+    let with_specifiers = |span, specifiers| ImportDecl {
+        span,
+        specifiers,
+        ..first.clone()
+    };
+
+    let mut namespaces = namespaces.into_iter();
+    let mut merged = vec![];
+
+    match (default, named.is_empty()) {
+        (None, true) => {}
+        (Some(default), true) => {
+            let mut specifiers = vec![ImportSpecifier::Default(default)];
+            specifiers.extend(namespaces.next());
+            merged.push(with_specifiers(first.span, specifiers));
+        }
+        (default, false) => {
+            let mut specifiers: Vec<_> =
+                default.map(ImportSpecifier::Default).into_iter().collect();
+            specifiers.extend(named);
+            merged.push(with_specifiers(first.span, specifiers));
+        }
+    }
+
+    for namespace in namespaces {
+        let span = if merged.is_empty() {
+            first.span
+        } else {
+            DUMMY_SP
+        };
+        merged.push(with_specifiers(span, vec![namespace]));
+    }
+
+    // only side imports
+    if merged.is_empty() {
+        merged.push(first);
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod test {
+    use super::merge_imports;
+    use crate::testing::assert_transform;
+
+    #[test]
+    fn imports_from_same_source_should_merge() {
+        assert_transform(
+            |_| merge_imports(),
+            r#"
+            import { a } from "x";
+            import "y";
+            import { b as c } from "x";
+            import d from "x";
+            import e from "x";
+            import "x";
+            import "y";
+            console.log(a, c, d, e);
+            "#,
+            r#"
+            import d, { a, b as c, default as e } from "x";
+            import "y";
+            console.log(a, c, d, e);
+            "#,
+        );
+    }
+
+    #[test]
+    fn namespace_imports_should_merge_with_default_only() {
+        assert_transform(
+            |_| merge_imports(),
+            r#"
+            import * as ns from "x";
+            import d from "x";
+            import * as ns2 from "x";
+            import { a } from "y";
+            import * as ns3 from "y";
+            "#,
+            r#"
+            import d, * as ns from "x";
+            import * as ns2 from "x";
+            import { a } from "y";
+            import * as ns3 from "y";
+            "#,
+        );
+    }
+
+    #[test]
+    fn imports_with_different_attributes_or_type_only_should_not_merge() {
+        assert_transform(
+            |_| merge_imports(),
+            r#"
+            import a from "./a.json" with { type: "json" };
+            import { b } from "./a.json";
+            import { c } from "./a.json" with { type: "json" };
+            import type { T } from "x";
+            import { x } from "x";
+            import type { U } from "x";
+            "#,
+            r#"
+            import a, { c } from "./a.json" with { type: "json" };
+            import { b } from "./a.json";
+            import type { T, U } from "x";
+            import { x } from "x";
+            "#,
+        );
+    }
+}
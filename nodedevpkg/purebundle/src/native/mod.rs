@@ -0,0 +1,245 @@
+use swc_core::common::comments::SingleThreadedComments;
+use swc_core::common::source_map::DefaultSourceMapGenConfig;
+use swc_core::common::sync::Lrc;
+use swc_core::common::{FileName, Mark, SourceMap, Spanned, GLOBALS};
+use swc_core::ecma::codegen::text_writer::JsWriter;
+use swc_core::ecma::codegen::Emitter;
+use swc_core::ecma::parser::error::Error;
+use swc_core::ecma::parser::{EsSyntax, Parser, StringInput, Syntax};
+use swc_core::ecma::transforms::base::resolver;
+
+use crate::config::Config;
+use crate::report::{collect, locate, ReportEntry};
+
+#[derive(Debug, Default, Clone)]
+pub struct TransformOptions {
+    /// used in errors and `sources` of source map
+    pub filename: String,
+    pub config: Config,
+    /// generate source map
+    pub source_map: bool,
+    /// source map of input, which generated source map will be chained to
+    pub input_source_map: Option<String>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TransformOutput {
+    pub code: String,
+    pub map: Option<String>,
+    /// entries of every transformation when `config.report`
+    pub report: Vec<ReportEntry>,
+}
+
+/// Transform JS source text like the swc plugin, without `@swc/core`.
+pub fn transform_code(source: &str, options: &TransformOptions) -> Result<TransformOutput, String> {
+    GLOBALS.set(&Default::default(), || {
+        transform_code_with_globals(source, options)
+    })
+}
+
+fn transform_code_with_globals(
+    source: &str,
+    options: &TransformOptions,
+) -> Result<TransformOutput, String> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(
+        Lrc::new(FileName::Custom(options.filename.clone())),
+        source.to_string(),
+    );
+
+    let comments = SingleThreadedComments::default();
+
+    let mut parser = Parser::new(
+        Syntax::Es(EsSyntax {
+            import_attributes: true,
+            ..Default::default()
+        }),
+        StringInput::from(&*fm),
+        Some(&comments),
+    );
+
+    let program = parser
+        .parse_program()
+        .map_err(|err| format_error(&cm, &options.filename, &err))?;
+
+    // recovered errors, like legacy octal literals in modules, fail the transform too
+    let errors = parser.take_errors();
+
+    if !errors.is_empty() {
+        return Err(errors
+            .iter()
+            .map(|err| format_error(&cm, &options.filename, err))
+            .collect::<Vec<_>>()
+            .join("\n"));
+    }
+
+    let program = program.apply(&mut resolver(Mark::new(), Mark::new(), false));
+
+    let (program, mut report) = collect(options.config.report, || {
+        crate::transform(program, &comments, &options.config)
+    });
+
+    locate(&mut report, &*cm);
+
+    let mut buf = vec![];
+    let mut mappings = vec![];
+
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: Some(&comments),
+            wr: JsWriter::new(
+                cm.clone(),
+                "\n",
+                &mut buf,
+                options.source_map.then_some(&mut mappings),
+            ),
+        };
+
+        emitter
+            .emit_program(&program)
+            .map_err(|err| format!("{}: {}", options.filename, err))?;
+    }
+
+    let map = if options.source_map {
+        let input_source_map = match &options.input_source_map {
+            Some(map) => Some(
+                swc_sourcemap::SourceMap::from_slice(map.as_bytes()).map_err(|err| {
+                    format!("{}: invalid input source map: {}", options.filename, err)
+                })?,
+            ),
+            None => None,
+        };
+
+        let mut map = vec![];
+        cm.build_source_map(&mappings, input_source_map, DefaultSourceMapGenConfig)
+            .to_writer(&mut map)
+            .map_err(|err| format!("{}: {}", options.filename, err))?;
+
+        Some(String::from_utf8_lossy(&map).to_string())
+    } else {
+        None
+    };
+
+    Ok(TransformOutput {
+        code: String::from_utf8_lossy(&buf).to_string(),
+        map,
+        report,
+    })
+}
+
+fn format_error(cm: &SourceMap, filename: &str, err: &Error) -> String {
+    let loc = cm.lookup_char_pos(err.span().lo);
+    format!(
+        "{}:{}:{}: {}",
+        filename,
+        loc.line,
+        loc.col.0 + 1,
+        err.kind().msg()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{transform_code, TransformOptions};
+    use crate::config::Config;
+
+    #[test]
+    fn code_should_be_transformed_with_source_map() {
+        let output = transform_code(
+            "import \"x\";\nexport const a = create();\n",
+            &TransformOptions {
+                filename: "index.mjs".into(),
+                source_map: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(output.code, "export const a = /*#__PURE__*/ create();\n");
+
+        let map = swc_sourcemap::SourceMap::from_slice(output.map.unwrap().as_bytes()).unwrap();
+        assert_eq!(map.get_source(0).map(|s| &**s), Some("index.mjs"));
+
+        // `create` after `/*#__PURE__*/ `
+        let token = map.lookup_token(0, 31).unwrap();
+        assert_eq!((token.get_src_line(), token.get_src_col()), (1, 17));
+    }
+
+    #[test]
+    fn source_map_should_chain_to_input_source_map() {
+        // `export const a = create();` compiled from line 3 of `src/index.ts`
+        let input_source_map =
+            r#"{"version":3,"sources":["src/index.ts"],"names":[],"mappings":";;AAEA"}"#;
+
+        let output = transform_code(
+            "\n\nexport const a = create();\n",
+            &TransformOptions {
+                filename: "index.mjs".into(),
+                source_map: true,
+                input_source_map: Some(input_source_map.into()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let map = swc_sourcemap::SourceMap::from_slice(output.map.unwrap().as_bytes()).unwrap();
+        assert_eq!(map.get_source(0).map(|s| &**s), Some("src/index.ts"));
+    }
+
+    #[test]
+    fn report_should_be_located() {
+        let output = transform_code(
+            "import \"x\";\nexport const a = create();\n",
+            &TransformOptions {
+                filename: "index.mjs".into(),
+                config: Config::from_json(r#"{ "report": true }"#).unwrap(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let located: Vec<_> = output
+            .report
+            .iter()
+            .map(|entry| (entry.rule, entry.text.as_str(), entry.line, entry.column))
+            .collect();
+
+        assert_eq!(
+            located,
+            vec![
+                ("ignoreSideImports", "x", 1, 0),
+                ("annotatePureCalls", "create", 2, 17),
+            ]
+        );
+    }
+
+    #[test]
+    fn syntax_error_should_be_reported() {
+        let err = transform_code(
+            "export const = 1",
+            &TransformOptions {
+                filename: "index.mjs".into(),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+
+        assert!(err.starts_with("index.mjs:1:"), "{}", err);
+    }
+
+    #[test]
+    fn recovered_syntax_error_should_be_reported() {
+        let err = transform_code(
+            "export const a = 1;\nexport const b = 010;\n",
+            &TransformOptions {
+                filename: "index.mjs".into(),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+
+        assert!(err.starts_with("index.mjs:2:"), "{}", err);
+    }
+}
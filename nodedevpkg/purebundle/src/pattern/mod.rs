@@ -0,0 +1,70 @@
+use regex::Regex;
+use serde::Deserialize;
+
+/// Pattern to match module specifiers
+///
+/// `/<regex>/` for regular expression, others as glob (`*` will match `/` too).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Pattern {
+    Glob(glob::Pattern),
+    Regex(Regex),
+}
+
+impl Pattern {
+    pub fn matches(&self, value: &str) -> bool {
+        match self {
+            Pattern::Glob(pattern) => pattern.matches(value),
+            Pattern::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+impl TryFrom<String> for Pattern {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.len() > 1 && value.starts_with('/') && value.ends_with('/') {
+            return Regex::new(&value[1..value.len() - 1])
+                .map(Pattern::Regex)
+                .map_err(|err| format!("invalid regex pattern `{}`: {}", value, err));
+        }
+
+        glob::Pattern::new(&value)
+            .map(Pattern::Glob)
+            .map_err(|err| format!("invalid glob pattern `{}`: {}", value, err))
+    }
+}
+
+pub fn matches_any(patterns: &[Pattern], value: &str) -> bool {
+    patterns.iter().any(|pattern| pattern.matches(value))
+}
+
+#[cfg(test)]
+mod test {
+    use super::Pattern;
+
+    fn pattern(s: &str) -> Pattern {
+        Pattern::try_from(s.to_string()).unwrap()
+    }
+
+    #[test]
+    fn glob_should_match_across_path_separators() {
+        assert!(pattern("*.css").matches("./theme.css"));
+        assert!(pattern("*.css").matches("bootstrap/dist/css/bootstrap.css"));
+        assert!(pattern("core-js/*").matches("core-js/stable/array"));
+        assert!(!pattern("*.css").matches("./theme.css.js"));
+    }
+
+    #[test]
+    fn regex_should_be_wrapped_by_slashes() {
+        assert!(pattern("/^reflect-metadata$/").matches("reflect-metadata"));
+        assert!(!pattern("/^reflect-metadata$/").matches("reflect-metadata/lite"));
+    }
+
+    #[test]
+    fn invalid_pattern_should_be_reported() {
+        assert!(Pattern::try_from("/(/".to_string()).is_err());
+        assert!(Pattern::try_from("[".to_string()).is_err());
+    }
+}
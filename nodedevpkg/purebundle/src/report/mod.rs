@@ -0,0 +1,144 @@
+use std::cell::RefCell;
+
+use serde::Serialize;
+use swc_core::common::comments::{Comment, CommentKind, Comments};
+use swc_core::common::{BytePos, SourceMapper, Span, DUMMY_SP};
+
+/// Flag of leading comment carrying the report, `/*#__PUREBUNDLE_REPORT__ [...]*/`
+pub const REPORT_FLAG: &str = "#__PUREBUNDLE_REPORT__";
+
+/// What a pass changed and why.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportEntry {
+    /// option name of the pass, like `annotatePureCalls`
+    pub rule: &'static str,
+    pub reason: &'static str,
+    /// callee, binding name or import source
+    pub text: String,
+    pub lo: u32,
+    pub hi: u32,
+    /// 1-based, 0 when unknown
+    pub line: usize,
+    /// 0-based
+    pub column: usize,
+}
+
+thread_local! {
+    static ENTRIES: RefCell<Option<Vec<ReportEntry>>> = const { RefCell::new(None) };
+}
+
+/// Record an entry when collecting by [collect], `text` is only evaluated then.
+pub fn report<F>(rule: &'static str, reason: &'static str, span: Span, text: F)
+where
+    F: FnOnce() -> String,
+{
+    ENTRIES.with(|entries| {
+        if let Some(entries) = entries.borrow_mut().as_mut() {
+            entries.push(ReportEntry {
+                rule,
+                reason,
+                text: text(),
+                lo: span.lo.0,
+                hi: span.hi.0,
+                line: 0,
+                column: 0,
+            });
+        }
+    });
+}
+
+/// Entries reported while `f` running, always empty when not `enabled`.
+pub fn collect<T, F>(enabled: bool, f: F) -> (T, Vec<ReportEntry>)
+where
+    F: FnOnce() -> T,
+{
+    if !enabled {
+        return (f(), vec![]);
+    }
+
+    let prev = ENTRIES.with(|entries| entries.borrow_mut().replace(vec![]));
+    let ret = f();
+    let collected = ENTRIES.with(|entries| entries.replace(prev));
+
+    (ret, collected.unwrap_or_default())
+}
+
+/// Fill line and column of entries by source map.
+pub fn locate<S: SourceMapper + ?Sized>(entries: &mut [ReportEntry], source_map: &S) {
+    for entry in entries {
+        if !BytePos(entry.lo).is_dummy() {
+            let loc = source_map.lookup_char_pos(BytePos(entry.lo));
+            entry.line = loc.line;
+            entry.column = loc.col.0;
+        }
+    }
+}
+
+/// Attach report as leading comment at `pos`, to be picked up from output.
+pub fn add_report_comment<C: Comments>(comments: &C, pos: BytePos, entries: &[ReportEntry]) {
+    // `*/` in sources would end the comment
+    let json = serde_json::to_string(entries)
+        .unwrap_or_default()
+        .replace("*/", "*\\/");
+
+    comments.add_leading(
+        pos,
+        Comment {
+            kind: CommentKind::Block,
+            span: DUMMY_SP,
+            text: format!("{} {}", REPORT_FLAG, json).into(),
+        },
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use swc_core::common::comments::{Comments, SingleThreadedComments};
+    use swc_core::common::{BytePos, Span};
+
+    use super::{add_report_comment, collect, report};
+    use crate::annotate_pure_calls::annotate_pure_calls;
+    use crate::testing::assert_transform;
+
+    #[test]
+    fn entries_should_be_collected_only_when_enabled() {
+        let (_, entries) = collect(false, || {
+            report("rule", "reason", Span::new(BytePos(1), BytePos(2)), || {
+                "x".into()
+            })
+        });
+        assert!(entries.is_empty());
+
+        let (_, entries) = collect(true, || {
+            assert_transform(
+                |comments| annotate_pure_calls(comments, &Default::default()),
+                "export const a = styled.div(x);",
+                "export const a = /*#__PURE__*/ styled.div(x);",
+            )
+        });
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].rule, "annotatePureCalls");
+        assert_eq!(entries[0].reason, "aggressive");
+        assert_eq!(entries[0].text, "styled.div");
+
+        report("rule", "reason", Span::default(), || unreachable!());
+    }
+
+    #[test]
+    fn report_comment_should_escape_comment_end() {
+        let (_, entries) = collect(true, || {
+            report("rule", "reason", Span::default(), || "*/x".into())
+        });
+
+        let comments = SingleThreadedComments::default();
+        add_report_comment(&comments, BytePos(1), &entries);
+
+        let text = comments.take_leading(BytePos(1)).unwrap()[0]
+            .text
+            .to_string();
+        assert!(text.starts_with("#__PUREBUNDLE_REPORT__ [{"), "{}", text);
+        assert!(text.contains(r#""text":"*\/x""#), "{}", text);
+    }
+}
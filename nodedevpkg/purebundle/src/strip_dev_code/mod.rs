@@ -0,0 +1,675 @@
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+use serde::Deserialize;
+use swc_core::common::util::take::Take;
+use swc_core::common::{Span, Spanned, DUMMY_SP};
+use swc_core::ecma::ast::{
+    ArrowExpr, BinExpr, BinaryOp, BindingIdent, BlockStmt, Bool, Callee, Class, Decl, Expr,
+    Function, Id, Ident, Lit, MemberProp, MetaPropKind, Module, ModuleItem, Null, Number, Pass,
+    Pat, Script, SimpleAssignTarget, Stmt, Str, UnaryOp, UpdateExpr, VarDecl, VarDeclKind,
+    VarDeclarator,
+};
+use swc_core::ecma::utils::{collect_decls, find_pat_ids};
+use swc_core::ecma::visit::{
+    noop_visit_mut_type, noop_visit_type, visit_mut_pass, Visit, VisitMut, VisitMutWith, VisitWith,
+};
+
+use crate::pattern::{matches_any, Pattern};
+use crate::report::report;
+
+/// Compile-time constant, JSON values except arrays and objects
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum DefineValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields, default)]
+pub struct StripDevCodeConfig {
+    /// off by default, dropped branches and calls change behavior of the output
+    pub enabled: bool,
+    /// global expressions replaced by constants, like `process.env.NODE_ENV` or `import.meta.env.DEV`
+    pub define: HashMap<String, DefineValue>,
+    /// methods of `console` whose call statements are dropped, like `debug` or `*`
+    pub drop_console: Vec<Pattern>,
+    /// drop `debugger` statements
+    pub drop_debugger: bool,
+}
+
+pub fn strip_dev_code(config: &StripDevCodeConfig) -> impl VisitMut + Pass {
+    visit_mut_pass(StripDevCode {
+        define: config.define.clone(),
+        drop_console: config.drop_console.clone(),
+        drop_debugger: config.drop_debugger,
+        decls: HashSet::new(),
+        write_target: false,
+        stripped: false,
+    })
+}
+
+struct StripDevCode {
+    define: HashMap<String, DefineValue>,
+    drop_console: Vec<Pattern>,
+    drop_debugger: bool,
+    decls: HashSet<Id>,
+    // set before visiting an expression written by assignment or update
+    write_target: bool,
+    // set when the statement just visited is replaced by a branch or dropped,
+    // so the enclosing statement list could flatten or remove it
+    stripped: bool,
+}
+
+impl StripDevCode {
+    // `process.env.NODE_ENV` or `import.meta.env.DEV`, root identifier should be global
+    fn global_path(&self, expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::Ident(ident) if !self.decls.contains(&ident.to_id()) => {
+                Some(ident.sym.to_string())
+            }
+            Expr::MetaProp(meta_prop) if meta_prop.kind == MetaPropKind::ImportMeta => {
+                Some("import.meta".into())
+            }
+            Expr::Member(member_expr) => {
+                let obj = self.global_path(&member_expr.obj)?;
+
+                let prop = match &member_expr.prop {
+                    MemberProp::Ident(ident) => ident.sym.to_string(),
+                    MemberProp::Computed(computed) => match computed.expr.as_ref() {
+                        Expr::Lit(Lit::Str(s)) => s.value.to_string_lossy().to_string(),
+                        _ => return None,
+                    },
+                    MemberProp::PrivateName(_) => return None,
+                };
+
+                Some(format!("{}.{}", obj, prop))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_dropped_console(&self, expr: &Expr) -> Option<String> {
+        let Expr::Call(call_expr) = expr else {
+            return None;
+        };
+
+        let Callee::Expr(callee) = &call_expr.callee else {
+            return None;
+        };
+
+        let path = self.global_path(callee)?;
+
+        match path.strip_prefix("console.") {
+            Some(method) if matches_any(&self.drop_console, method) => Some(path),
+            _ => None,
+        }
+    }
+
+    // taken branch of `if` with constant test, `;` when none
+    fn strip_stmt(&self, stmt: &mut Stmt) -> bool {
+        match stmt {
+            Stmt::If(if_stmt) => {
+                let Some(test) = eval(&if_stmt.test) else {
+                    return false;
+                };
+
+                report("stripDevCode", "dead branch", if_stmt.span, || "if".into());
+
+                let (taken, dropped) = if test.truthy() {
+                    (Some(if_stmt.cons.take()), if_stmt.alt.take())
+                } else {
+                    (if_stmt.alt.take(), Some(if_stmt.cons.take()))
+                };
+
+                let taken = taken.map_or_else(Stmt::dummy, |taken| *taken);
+
+                // `var` of dropped branch is still hoisted, keep it declared as `var x;`
+                let vars = dropped
+                    .map(|dropped| hoisted_vars(&dropped))
+                    .unwrap_or_default();
+
+                *stmt = if vars.is_empty() {
+                    taken
+                } else {
+                    let mut stmts = vec![Stmt::Decl(Decl::Var(Box::new(VarDecl {
+                        span: DUMMY_SP,
+                        kind: VarDeclKind::Var,
+                        decls: vars
+                            .into_iter()
+                            .map(|id| VarDeclarator {
+                                span: DUMMY_SP,
+                                name: Pat::Ident(BindingIdent::from(Ident::from(id))),
+                                init: None,
+                                definite: false,
+                            })
+                            .collect(),
+                        ..Default::default()
+                    })))];
+
+                    match taken {
+                        Stmt::Empty(_) => {}
+                        Stmt::Block(block_stmt) if !has_lexical_decl(&block_stmt.stmts) => {
+                            stmts.extend(block_stmt.stmts)
+                        }
+                        taken => stmts.push(taken),
+                    }
+
+                    Stmt::Block(BlockStmt {
+                        stmts,
+                        ..Default::default()
+                    })
+                };
+
+                true
+            }
+            Stmt::Debugger(debugger_stmt) if self.drop_debugger => {
+                report("stripDevCode", "debugger", debugger_stmt.span, || {
+                    "debugger".into()
+                });
+
+                *stmt = Stmt::dummy();
+                true
+            }
+            Stmt::Expr(expr_stmt) => {
+                // leftover of folded `__DEV__ && warn()`, strings may be directives
+                if matches!(
+                    &*expr_stmt.expr,
+                    Expr::Lit(Lit::Bool(_) | Lit::Null(_) | Lit::Num(_))
+                ) {
+                    *stmt = Stmt::dummy();
+                    return true;
+                }
+
+                if let Some(path) = self.is_dropped_console(&expr_stmt.expr) {
+                    report("stripDevCode", "console", expr_stmt.span, || path);
+
+                    *stmt = Stmt::dummy();
+                    return true;
+                }
+
+                false
+            }
+            _ => false,
+        }
+    }
+
+    fn flatten(&mut self, stmt: Stmt, stmts: &mut Vec<Stmt>) {
+        if !mem::take(&mut self.stripped) {
+            stmts.push(stmt);
+            return;
+        }
+
+        match stmt {
+            Stmt::Empty(_) => {}
+            Stmt::Block(block_stmt) if !has_lexical_decl(&block_stmt.stmts) => {
+                stmts.extend(block_stmt.stmts)
+            }
+            stmt => stmts.push(stmt),
+        }
+    }
+}
+
+impl VisitMut for StripDevCode {
+    noop_visit_mut_type!();
+
+    fn visit_mut_module(&mut self, module: &mut Module) {
+        self.decls = collect_decls::<Id, _>(&*module).into_iter().collect();
+        module.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_script(&mut self, script: &mut Script) {
+        self.decls = collect_decls::<Id, _>(&*script).into_iter().collect();
+        script.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        // `process.env.X = 1` or `process.env.X++` should be kept as is
+        if mem::take(&mut self.write_target) {
+            if let Expr::Paren(paren_expr) = expr {
+                self.write_target = true;
+                paren_expr.expr.visit_mut_with(self);
+            } else {
+                expr.visit_mut_children_with(self);
+            }
+            return;
+        }
+
+        if let Some(value) = self
+            .global_path(expr)
+            .and_then(|path| self.define.get(&path).map(|value| (path, value)))
+            .map(|(path, value)| {
+                report("stripDevCode", "define", expr.span(), || path);
+                value.to_lit(expr.span())
+            })
+        {
+            *expr = Expr::Lit(value);
+            return;
+        }
+
+        expr.visit_mut_children_with(self);
+
+        match expr {
+            Expr::Cond(cond_expr) => {
+                if let Some(test) = eval(&cond_expr.test) {
+                    report("stripDevCode", "dead branch", cond_expr.span, || {
+                        "conditional".into()
+                    });
+
+                    *expr = if test.truthy() {
+                        *cond_expr.cons.take()
+                    } else {
+                        *cond_expr.alt.take()
+                    };
+                }
+            }
+            Expr::Bin(bin_expr) => {
+                if let Some(folded) = fold_bin(bin_expr) {
+                    *expr = folded;
+                }
+            }
+            Expr::Unary(unary_expr) if unary_expr.op == UnaryOp::Bang => {
+                if let Some(arg) = eval(&unary_expr.arg) {
+                    *expr = Value::Bool(!arg.truthy()).to_expr(unary_expr.span);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn visit_mut_update_expr(&mut self, update_expr: &mut UpdateExpr) {
+        self.write_target = true;
+        update_expr.arg.visit_mut_with(self);
+    }
+
+    fn visit_mut_simple_assign_target(&mut self, target: &mut SimpleAssignTarget) {
+        if let SimpleAssignTarget::Paren(paren_expr) = target {
+            self.write_target = true;
+            paren_expr.expr.visit_mut_with(self);
+            return;
+        }
+
+        target.visit_mut_children_with(self);
+    }
+
+    // `[process.env.X] = value` or `for (process.env.X of value)`
+    fn visit_mut_pat(&mut self, pat: &mut Pat) {
+        if let Pat::Expr(expr) = pat {
+            self.write_target = true;
+            expr.visit_mut_with(self);
+            return;
+        }
+
+        pat.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_stmt(&mut self, stmt: &mut Stmt) {
+        stmt.visit_mut_children_with(self);
+        self.stripped = self.strip_stmt(stmt);
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        let mut stripped = Vec::with_capacity(stmts.len());
+
+        for mut stmt in mem::take(stmts) {
+            stmt.visit_mut_with(self);
+            self.flatten(stmt, &mut stripped);
+        }
+
+        *stmts = stripped;
+    }
+
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        let mut stripped = Vec::with_capacity(items.len());
+
+        for item in mem::take(items) {
+            match item {
+                ModuleItem::Stmt(mut stmt) => {
+                    stmt.visit_mut_with(self);
+
+                    let mut stmts = vec![];
+                    self.flatten(stmt, &mut stmts);
+                    stripped.extend(stmts.into_iter().map(ModuleItem::Stmt));
+                }
+                mut item => {
+                    item.visit_mut_with(self);
+                    stripped.push(item);
+                }
+            }
+        }
+
+        *items = stripped;
+    }
+}
+
+// `var` bindings declared in stmt, not in nested functions
+fn hoisted_vars(stmt: &Stmt) -> Vec<Id> {
+    let mut visitor = HoistedVars::default();
+    stmt.visit_with(&mut visitor);
+    visitor.ids
+}
+
+#[derive(Default)]
+struct HoistedVars {
+    ids: Vec<Id>,
+}
+
+impl Visit for HoistedVars {
+    noop_visit_type!();
+
+    fn visit_var_decl(&mut self, var_decl: &VarDecl) {
+        if var_decl.kind == VarDeclKind::Var {
+            for id in find_pat_ids::<_, Id>(&var_decl.decls) {
+                if !self.ids.contains(&id) {
+                    self.ids.push(id);
+                }
+            }
+        }
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_class(&mut self, _: &Class) {}
+}
+
+// block could be inlined without changing scope of bindings
+fn has_lexical_decl(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        Stmt::Decl(Decl::Var(var_decl)) => var_decl.kind != VarDeclKind::Var,
+        Stmt::Decl(_) => true,
+        _ => false,
+    })
+}
+
+#[derive(Debug, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+}
+
+impl Value {
+    fn truthy(&self) -> bool {
+        match self {
+            Value::Null => false,
+            Value::Bool(value) => *value,
+            Value::Number(value) => *value != 0.0 && !value.is_nan(),
+            Value::String(value) => !value.is_empty(),
+        }
+    }
+
+    // `==` of primitives, `None` when coercion needed
+    fn loose_eq(&self, other: &Value) -> Option<bool> {
+        match (self, other) {
+            (Value::Null, Value::Null) => Some(true),
+            (Value::Null, _) | (_, Value::Null) => Some(false),
+            (a, b) if mem::discriminant(a) == mem::discriminant(b) => Some(a == b),
+            _ => None,
+        }
+    }
+
+    fn to_expr(&self, span: Span) -> Expr {
+        Expr::Lit(match self {
+            Value::Null => Lit::Null(Null { span }),
+            Value::Bool(value) => Lit::Bool(Bool {
+                span,
+                value: *value,
+            }),
+            Value::Number(value) => Lit::Num(Number {
+                span,
+                value: *value,
+                raw: None,
+            }),
+            Value::String(value) => Lit::Str(Str {
+                span,
+                value: value.as_str().into(),
+                raw: None,
+            }),
+        })
+    }
+}
+
+impl DefineValue {
+    fn to_lit(&self, span: Span) -> Lit {
+        let value = match self {
+            DefineValue::Null => Value::Null,
+            DefineValue::Bool(value) => Value::Bool(*value),
+            DefineValue::Number(value) => Value::Number(*value),
+            DefineValue::String(value) => Value::String(value.clone()),
+        };
+
+        match value.to_expr(span) {
+            Expr::Lit(lit) => lit,
+            _ => unreachable!("constants are literals"),
+        }
+    }
+}
+
+fn eval(expr: &Expr) -> Option<Value> {
+    match expr.unwrap_parens() {
+        Expr::Lit(Lit::Null(_)) => Some(Value::Null),
+        Expr::Lit(Lit::Bool(value)) => Some(Value::Bool(value.value)),
+        Expr::Lit(Lit::Num(value)) => Some(Value::Number(value.value)),
+        Expr::Lit(Lit::Str(value)) => {
+            Some(Value::String(value.value.to_string_lossy().to_string()))
+        }
+        _ => None,
+    }
+}
+
+// comparisons of constants and logical expressions with constant left
+fn fold_bin(bin_expr: &mut BinExpr) -> Option<Expr> {
+    let left = eval(&bin_expr.left)?;
+
+    let right = match bin_expr.op {
+        BinaryOp::LogicalAnd | BinaryOp::LogicalOr | BinaryOp::NullishCoalescing => {
+            let take_right = match bin_expr.op {
+                BinaryOp::LogicalAnd => left.truthy(),
+                BinaryOp::LogicalOr => !left.truthy(),
+                _ => left == Value::Null,
+            };
+
+            if take_right {
+                return Some(*bin_expr.right.take());
+            }
+
+            report("stripDevCode", "dead branch", bin_expr.span, || {
+                bin_expr.op.as_str().into()
+            });
+
+            return Some(*bin_expr.left.take());
+        }
+        _ => eval(&bin_expr.right)?,
+    };
+
+    let result = match bin_expr.op {
+        BinaryOp::EqEqEq => left == right,
+        BinaryOp::NotEqEq => left != right,
+        BinaryOp::EqEq => left.loose_eq(&right)?,
+        BinaryOp::NotEq => !left.loose_eq(&right)?,
+        _ => return None,
+    };
+
+    Some(Value::Bool(result).to_expr(bin_expr.span))
+}
+
+#[cfg(test)]
+mod test {
+    use super::strip_dev_code;
+    use crate::config::Config;
+    use crate::testing::assert_transform;
+
+    fn config(json: &str) -> super::StripDevCodeConfig {
+        Config::from_json(json).unwrap().strip_dev_code
+    }
+
+    #[test]
+    fn dev_branches_should_be_folded_by_define() {
+        let config = config(
+            r#"{ "stripDevCode": { "define": { "process.env.NODE_ENV": "production", "import.meta.env.DEV": false } } }"#,
+        );
+
+        assert_transform(
+            |_| strip_dev_code(&config),
+            r#"
+            if (process.env.NODE_ENV !== "production") {
+                validate(props);
+            }
+            if (import.meta.env.DEV) {
+                const x = 1;
+                console.log(x);
+            } else {
+                var mode = "prod";
+            }
+            process.env.NODE_ENV === "development" && warn();
+            export const level = process.env["NODE_ENV"] == "production" ? 0 : 1;
+            export const dev = !import.meta.env.DEV || debug;
+            function setup() {
+                "use strict";
+                if (import.meta.env.DEV) {
+                    return;
+                }
+                init();
+            }
+            "#,
+            r#"
+            var mode = "prod";
+            export const level = 0;
+            export const dev = true;
+            function setup() {
+                "use strict";
+                init();
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn vars_of_dropped_branches_should_be_kept_declared() {
+        let config =
+            config(r#"{ "stripDevCode": { "define": { "process.env.NODE_ENV": "production" } } }"#);
+
+        assert_transform(
+            |_| strip_dev_code(&config),
+            r#"
+            if (process.env.NODE_ENV !== "production") {
+                var cache = new Map();
+                var { hits, misses } = stats;
+                const seen = new Set();
+                function track() {
+                    var calls = 0;
+                }
+            }
+            if (process.env.NODE_ENV === "production") {
+                setup();
+            } else {
+                for (var i = 0; i < 3; i++) {}
+            }
+            export function get(k) {
+                return cache && cache.get(k);
+            }
+            "#,
+            r#"
+            var cache, hits, misses;
+            var i;
+            setup();
+            export function get(k) {
+                return cache && cache.get(k);
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn local_bindings_should_not_be_defined() {
+        let config = config(
+            r#"{ "stripDevCode": { "define": { "process.env.NODE_ENV": "production", "__DEV__": false } } }"#,
+        );
+
+        assert_transform(
+            |_| strip_dev_code(&config),
+            r#"
+            import process from "process";
+            if (process.env.NODE_ENV !== "production") {
+                validate(props);
+            }
+            if (__DEV__) {
+                let x = 1;
+            } else {
+                let y = 2;
+            }
+            "#,
+            r#"
+            import process from "process";
+            if (process.env.NODE_ENV !== "production") {
+                validate(props);
+            }
+            {
+                let y = 2;
+            }
+            "#,
+        );
+    }
+
+    #[test]
+    fn console_and_debugger_should_be_dropped_when_configured() {
+        let config = config(
+            r#"{ "stripDevCode": { "dropConsole": ["debug", "log"], "dropDebugger": true } }"#,
+        );
+
+        assert_transform(
+            |_| strip_dev_code(&config),
+            r#"
+            console.debug("state", state);
+            console.error("failed");
+            function tick() {
+                debugger;
+                console.log(tick);
+            }
+            "#,
+            r#"
+            console.error("failed");
+            function tick() {}
+            "#,
+        );
+    }
+
+    #[test]
+    fn written_defines_should_not_be_replaced() {
+        let config = config(
+            r#"{ "stripDevCode": { "define": { "process.env.NODE_ENV": "production", "__DEV__": false } } }"#,
+        );
+
+        assert_transform(
+            |_| strip_dev_code(&config),
+            r#"
+            process.env.NODE_ENV = "test";
+            process.env.NODE_ENV++;
+            (process.env.NODE_ENV) = "test";
+            [process.env.NODE_ENV] = ["test"];
+            ({ mode: process.env.NODE_ENV } = options);
+            for (process.env.NODE_ENV of modes) {}
+            __DEV__ = true;
+            __DEV__--;
+            export const mode = process.env.NODE_ENV;
+            "#,
+            r#"
+            process.env.NODE_ENV = "test";
+            process.env.NODE_ENV++;
+            (process.env.NODE_ENV) = "test";
+            [process.env.NODE_ENV] = ["test"];
+            ({ mode: process.env.NODE_ENV } = options);
+            for (process.env.NODE_ENV of modes) {}
+            __DEV__ = true;
+            __DEV__--;
+            export const mode = "production";
+            "#,
+        );
+    }
+}
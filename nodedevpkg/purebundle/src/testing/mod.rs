@@ -0,0 +1,53 @@
+use std::rc::Rc;
+
+use swc_core::common::comments::SingleThreadedComments;
+use swc_core::common::Mark;
+use swc_core::ecma::ast::{noop_pass, Pass};
+use swc_core::ecma::parser::{Syntax, TsSyntax};
+use swc_core::ecma::transforms::base::resolver;
+use swc_core::ecma::transforms::testing::Tester;
+
+pub const SYNTAX: Syntax = Syntax::Typescript(TsSyntax {
+    tsx: true,
+    decorators: false,
+    dts: false,
+    no_early_errors: false,
+    disallow_ambiguous_jsx_like: true,
+});
+
+/// Like `test_inline!`, but comments will be printed,
+/// `tr` will be applied after `resolver`.
+pub fn assert_transform<F, P>(tr: F, input: &str, expected: &str)
+where
+    F: FnOnce(Rc<SingleThreadedComments>) -> P,
+    P: Pass,
+{
+    let expected_src = Tester::run(|tester| {
+        let program =
+            tester.apply_transform(noop_pass(), "expected.js", SYNTAX, Some(true), expected)?;
+
+        Ok(tester.print(&program, &tester.comments.clone()))
+    });
+
+    let actual_src = Tester::run(|tester| {
+        let comments = tester.comments.clone();
+
+        let program = tester.apply_transform(
+            (
+                resolver(Mark::new(), Mark::new(), false),
+                tr(comments.clone()),
+            ),
+            "input.js",
+            SYNTAX,
+            Some(true),
+            input,
+        )?;
+
+        Ok(tester.print(&program, &comments))
+    });
+
+    assert_eq!(
+        expected_src, actual_src,
+        "Expected:\n{expected_src}\nActual:\n{actual_src}\n",
+    );
+}
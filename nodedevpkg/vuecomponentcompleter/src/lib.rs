@@ -1,8 +1,10 @@
 use swc_core::plugin::metadata::TransformPluginProgramMetadata;
 use swc_core::{ecma::ast::Program, plugin::plugin_transform};
 
+mod native;
 mod vue_component_completer;
 
+pub use self::native::{transform_code, TransformOptions, TransformOutput};
 use self::vue_component_completer::*;
 
 #[plugin_transform]
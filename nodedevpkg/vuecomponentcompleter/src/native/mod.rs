@@ -0,0 +1,174 @@
+use swc_core::common::comments::SingleThreadedComments;
+use swc_core::common::source_map::DefaultSourceMapGenConfig;
+use swc_core::common::sync::Lrc;
+use swc_core::common::{FileName, Mark, SourceMap, Spanned, GLOBALS};
+use swc_core::ecma::codegen::text_writer::JsWriter;
+use swc_core::ecma::codegen::Emitter;
+use swc_core::ecma::parser::error::Error;
+use swc_core::ecma::parser::{Parser, StringInput, Syntax, TsSyntax};
+use swc_core::ecma::transforms::base::resolver;
+
+use crate::vue_component_completer::vue_component_completer;
+
+#[derive(Debug, Default, Clone)]
+pub struct TransformOptions {
+    /// used in errors and `sources` of source map, `.tsx` enables jsx
+    pub filename: String,
+    /// generate source map
+    pub source_map: bool,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TransformOutput {
+    pub code: String,
+    pub map: Option<String>,
+}
+
+/// Complete components of TS source text like the swc plugin, without `@swc/core`.
+/// Types are kept, stripping them is left to the bundler.
+pub fn transform_code(source: &str, options: &TransformOptions) -> Result<TransformOutput, String> {
+    GLOBALS.set(&Default::default(), || {
+        transform_code_with_globals(source, options)
+    })
+}
+
+fn transform_code_with_globals(
+    source: &str,
+    options: &TransformOptions,
+) -> Result<TransformOutput, String> {
+    let cm: Lrc<SourceMap> = Default::default();
+    let fm = cm.new_source_file(
+        Lrc::new(FileName::Custom(options.filename.clone())),
+        source.to_string(),
+    );
+
+    let comments = SingleThreadedComments::default();
+
+    let mut parser = Parser::new(
+        Syntax::Typescript(TsSyntax {
+            tsx: options.filename.ends_with(".tsx"),
+            ..Default::default()
+        }),
+        StringInput::from(&*fm),
+        Some(&comments),
+    );
+
+    let program = parser
+        .parse_program()
+        .map_err(|err| format_error(&cm, &options.filename, &err))?;
+
+    // recovered errors, like legacy octal literals in modules, fail the transform too
+    let errors = parser.take_errors();
+
+    if !errors.is_empty() {
+        return Err(errors
+            .iter()
+            .map(|err| format_error(&cm, &options.filename, err))
+            .collect::<Vec<_>>()
+            .join("\n"));
+    }
+
+    // same as the swc plugin, which runs after bindings resolved by `@swc/core`
+    let program = program.apply(&mut resolver(Mark::new(), Mark::new(), true));
+
+    let program = program.apply(&mut vue_component_completer());
+
+    let mut buf = vec![];
+    let mut mappings = vec![];
+
+    {
+        let mut emitter = Emitter {
+            cfg: Default::default(),
+            cm: cm.clone(),
+            comments: Some(&comments),
+            wr: JsWriter::new(
+                cm.clone(),
+                "\n",
+                &mut buf,
+                options.source_map.then_some(&mut mappings),
+            ),
+        };
+
+        emitter
+            .emit_program(&program)
+            .map_err(|err| format!("{}: {}", options.filename, err))?;
+    }
+
+    let map = if options.source_map {
+        let mut map = vec![];
+        cm.build_source_map(&mappings, None, DefaultSourceMapGenConfig)
+            .to_writer(&mut map)
+            .map_err(|err| format!("{}: {}", options.filename, err))?;
+
+        Some(String::from_utf8_lossy(&map).to_string())
+    } else {
+        None
+    };
+
+    Ok(TransformOutput {
+        code: String::from_utf8_lossy(&buf).to_string(),
+        map,
+    })
+}
+
+fn format_error(cm: &SourceMap, filename: &str, err: &Error) -> String {
+    let loc = cm.lookup_char_pos(err.span().lo);
+    format!(
+        "{}:{}:{}: {}",
+        filename,
+        loc.line,
+        loc.col.0 + 1,
+        err.kind().msg()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{transform_code, TransformOptions};
+
+    #[test]
+    fn tsx_should_be_completed_with_types_kept() {
+        let output = transform_code(
+            "export const X = component<{ active?: boolean }>(() => <div />);\n",
+            &TransformOptions {
+                filename: "X.tsx".into(),
+                source_map: true,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            output.code,
+            "export const X = component<{\n    active?: boolean;\n}>(()=><div/>, {\n    displayName: \"X\",\n    props: [\n        \"active\"\n    ]\n});\n"
+        );
+        assert!(output.map.is_some());
+    }
+
+    #[test]
+    fn ts_should_parse_type_assertions() {
+        let output = transform_code(
+            "const x = <number>y;\n",
+            &TransformOptions {
+                filename: "x.ts".into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(output.code, "const x = <number>y;\n");
+    }
+
+    #[test]
+    fn recovered_syntax_error_should_be_reported() {
+        let err = transform_code(
+            "export const a = 1;\nexport const b = 010;\n",
+            &TransformOptions {
+                filename: "x.ts".into(),
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+
+        assert!(err.starts_with("x.ts:2:"), "{}", err);
+    }
+}